@@ -0,0 +1,122 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use simple_blit::{blit, blit_with, size, GenericSurface, Transform};
+use std::hint::black_box;
+
+const SIZE: u32 = 1024;
+
+fn untransformed_copy(c: &mut Criterion) {
+    let src = vec![0x12345678_u32; (SIZE * SIZE) as usize];
+    let mut dest = vec![0_u32; (SIZE * SIZE) as usize];
+
+    let mut group = c.benchmark_group("copy 1024x1024");
+
+    group.bench_function("blit (row slices)", |b| {
+        b.iter(|| {
+            blit(
+                GenericSurface::new(&mut dest, size(SIZE, SIZE)).unwrap(),
+                GenericSurface::new(black_box(&src), size(SIZE, SIZE)).unwrap(),
+                &[],
+            )
+        })
+    });
+
+    group.bench_function("blit_with (per pixel)", |b| {
+        b.iter(|| {
+            blit_with(
+                GenericSurface::new(&mut dest, size(SIZE, SIZE)).unwrap(),
+                GenericSurface::new(black_box(&src), size(SIZE, SIZE)).unwrap(),
+                &[],
+                |dest, src, _| *dest = *src,
+            )
+        })
+    });
+
+    group.finish();
+}
+
+fn transformed_copy(c: &mut Criterion) {
+    // The no-op downscale is there on purpose: chains with `DownScale` can't be simplified,
+    // so the last case measures the unoptimized path that folds over every transform per value.
+    // If `simplify` ever learns to drop identity scales, replace it with a real downscale
+    // to keep this comparison.
+    const TRANSFORMS: [Transform; 9] = [
+        Transform::DownScale { x: 1, y: 1 },
+        Transform::Rotate90Cw,
+        Transform::FlipHorizontal,
+        Transform::Transpose,
+        Transform::Rotate180,
+        Transform::FlipVertical,
+        Transform::Rotate90Ccw,
+        Transform::FlipBoth,
+        Transform::Rotate90Cw,
+    ];
+
+    let src = vec![0x12345678_u32; (SIZE * SIZE) as usize];
+    let mut dest = vec![0_u32; (SIZE * SIZE) as usize];
+
+    let mut group = c.benchmark_group("transformed 1024x1024");
+
+    for (name, transforms) in [
+        ("single flip", &TRANSFORMS[2..3]),
+        ("8 transforms", &TRANSFORMS[1..]),
+        ("8 transforms and a downscale", &TRANSFORMS[..]),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                blit(
+                    GenericSurface::new(&mut dest, size(SIZE, SIZE)).unwrap(),
+                    GenericSurface::new(black_box(&src), size(SIZE, SIZE)).unwrap(),
+                    black_box(transforms),
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+#[cfg(feature = "rayon")]
+fn parallel_copy(c: &mut Criterion) {
+    use simple_blit::blit_par;
+
+    const SIZE: u32 = 4096;
+
+    let src = vec![0x12345678_u32; (SIZE * SIZE) as usize];
+    let mut dest = vec![0_u32; (SIZE * SIZE) as usize];
+
+    let mut group = c.benchmark_group("parallel 4096x4096");
+    group.sample_size(20);
+
+    for (name, transforms) in [
+        ("untransformed", &[][..]),
+        ("rotated", &[Transform::Rotate90Cw][..]),
+    ] {
+        group.bench_function(format!("blit ({name})"), |b| {
+            b.iter(|| {
+                blit(
+                    GenericSurface::new(&mut dest, size(SIZE, SIZE)).unwrap(),
+                    GenericSurface::new(black_box(&src), size(SIZE, SIZE)).unwrap(),
+                    black_box(transforms),
+                )
+            })
+        });
+
+        group.bench_function(format!("blit_par ({name})"), |b| {
+            b.iter(|| {
+                blit_par(
+                    &mut GenericSurface::new(&mut dest, size(SIZE, SIZE)).unwrap(),
+                    GenericSurface::new(black_box(&src), size(SIZE, SIZE)).unwrap(),
+                    black_box(transforms),
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+#[cfg(feature = "rayon")]
+criterion_group!(benches, untransformed_copy, transformed_copy, parallel_copy);
+#[cfg(not(feature = "rayon"))]
+criterion_group!(benches, untransformed_copy, transformed_copy);
+criterion_main!(benches);
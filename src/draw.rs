@@ -0,0 +1,285 @@
+use crate::{point, size, Point, SignedPoint, Size, SurfaceMut};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Draw a line from `from` to `to` (both inclusive) using Bresenham's algorithm.
+///
+/// The points may be outside of the surface, only the visible part of the line is drawn.
+/// The line is clipped to the surface before drawing, so far away points aren't slower to draw,
+/// and the drawn values are the same as if the whole line was walked.
+pub fn draw_line<T: Clone>(
+    mut dest: impl SurfaceMut<T>,
+    from: SignedPoint,
+    to: SignedPoint,
+    value: T,
+) {
+    let (x0, y0) = (from.x as i64, from.y as i64);
+    let (x1, y1) = (to.x as i64, to.y as i64);
+
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let step_x = if x0 < x1 { 1 } else { -1 };
+    let step_y = if y0 < y1 { 1 } else { -1 };
+
+    // the line moves along its major axis on every step, and after `k` steps it has moved
+    // `(2 * k * minor + major) / (2 * major)` times along the other one,
+    // so the steps that are inside the surface can be found without walking the rest
+    let x_major = dx >= -dy;
+    let (major, minor) = if x_major { (dx, -dy) } else { (-dy, dx) };
+    let minor_steps = |k: i64| {
+        ((2 * k as i128 * minor as i128 + major as i128) / (2 * major.max(1) as i128)) as i64
+    };
+
+    let surface_size = dest.surface_size();
+    let (major_range, minor_range) = if x_major {
+        (
+            axis_steps(x0, step_x, surface_size.x, major),
+            axis_steps(y0, step_y, surface_size.y, minor),
+        )
+    } else {
+        (
+            axis_steps(y0, step_y, surface_size.y, major),
+            axis_steps(x0, step_x, surface_size.x, minor),
+        )
+    };
+
+    let (Some((major_first, major_last)), Some((minor_first, minor_last))) =
+        (major_range, minor_range)
+    else {
+        return;
+    };
+
+    // the first and last steps with the wanted minor step counts
+    let first_with = |n: i64| {
+        if n == 0 {
+            0
+        } else {
+            let numerator = (2 * n as i128 - 1) * major as i128;
+            let denominator = 2 * minor as i128;
+            ((numerator + denominator - 1) / denominator) as i64
+        }
+    };
+    let last_with = |n: i64| {
+        if n == minor {
+            major
+        } else {
+            first_with(n + 1) - 1
+        }
+    };
+
+    let first = major_first.max(first_with(minor_first));
+    let last = major_last.min(last_with(minor_last));
+
+    if first > last {
+        return;
+    }
+
+    let moved = minor_steps(first);
+    let (mut x, mut y, mut error) = if x_major {
+        (
+            x0 + first * step_x,
+            y0 + moved * step_y,
+            dx + dy + (first as i128 * dy as i128 + moved as i128 * dx as i128) as i64,
+        )
+    } else {
+        (
+            x0 + moved * step_x,
+            y0 + first * step_y,
+            dx + dy + (moved as i128 * dy as i128 + first as i128 * dx as i128) as i64,
+        )
+    };
+
+    for _ in first..last {
+        put(&mut dest, x, y, &value);
+
+        let error2 = 2 * error;
+
+        if error2 >= dy {
+            error += dy;
+            x += step_x;
+        }
+
+        if error2 <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+
+    put(&mut dest, x, y, &value);
+}
+
+/// Range of step counts `n` in `0..=max` for which `start + n * step` is in `0..len`.
+#[inline]
+fn axis_steps(start: i64, step: i64, len: u32, max: i64) -> Option<(i64, i64)> {
+    let len = i64::from(len);
+
+    let (first, last) = if step > 0 {
+        (-start, len - 1 - start)
+    } else {
+        (start - (len - 1), start)
+    };
+    let (first, last) = (first.max(0), last.min(max));
+
+    (first <= last).then_some((first, last))
+}
+
+/// Fill a rectangle with `value`.
+///
+/// Only the part of the rectangle inside the surface is filled.
+#[inline]
+pub fn fill_rect<T: Clone>(mut dest: impl SurfaceMut<T>, offset: Point, size: Size, value: T) {
+    dest.sub_surface_mut(offset, size).fill(value);
+}
+
+/// Draw the outline of a rectangle.
+///
+/// Only the part of the outline inside the surface is drawn.
+pub fn draw_rect<T: Clone>(mut dest: impl SurfaceMut<T>, offset: Point, rect_size: Size, value: T) {
+    if rect_size.x == 0 || rect_size.y == 0 {
+        return;
+    }
+
+    let far = point(
+        offset.x.saturating_add(rect_size.x - 1),
+        offset.y.saturating_add(rect_size.y - 1),
+    );
+
+    let horizontal = size(rect_size.x, 1);
+    let vertical = size(1, rect_size.y);
+
+    fill_rect(&mut dest, offset, horizontal, value.clone());
+    fill_rect(&mut dest, point(offset.x, far.y), horizontal, value.clone());
+    fill_rect(&mut dest, offset, vertical, value.clone());
+    fill_rect(&mut dest, point(far.x, offset.y), vertical, value);
+}
+
+/// Draw the outline of a circle using the midpoint algorithm.
+///
+/// Only the part of the outline inside the surface is drawn.
+pub fn draw_circle<T: Clone>(mut dest: impl SurfaceMut<T>, center: Point, radius: u32, value: T) {
+    for_circle_octants(center, radius, |(cx, cy), x, y| {
+        for (dx, dy) in [
+            (x, y),
+            (y, x),
+            (-x, y),
+            (-y, x),
+            (x, -y),
+            (y, -x),
+            (-x, -y),
+            (-y, -x),
+        ] {
+            put(&mut dest, cx + dx, cy + dy, &value);
+        }
+    });
+}
+
+/// Draw a filled circle.
+///
+/// Only the part of the circle inside the surface is filled.
+pub fn fill_circle<T: Clone>(mut dest: impl SurfaceMut<T>, center: Point, radius: u32, value: T) {
+    for_circle_octants(center, radius, |(cx, cy), x, y| {
+        for (half_width, dy) in [(x, y), (x, -y), (y, x), (y, -x)] {
+            put_span(&mut dest, cx - half_width, cx + half_width, cy + dy, &value);
+        }
+    });
+}
+
+/// Replace the connected region of values equal to the value at `start` with `new_value`.
+///
+/// Uses a scanline algorithm with a heap-allocated work stack (one point per seeded span),
+/// which in the worst case (e.g. a checkerboard-like maze) may grow proportionally to the area of the region.
+#[cfg(feature = "alloc")]
+pub fn flood_fill<T: Clone + PartialEq>(mut dest: impl SurfaceMut<T>, start: Point, new_value: T) {
+    let target = match dest.surface_get(start) {
+        Some(target) if *target != new_value => target.clone(),
+        _ => return,
+    };
+
+    let size = dest.surface_size();
+    let mut stack = Vec::from([start]);
+
+    while let Some(seed) = stack.pop() {
+        if dest.surface_get(seed) != Some(&target) {
+            continue;
+        }
+
+        let mut left = seed.x;
+        while left > 0 && dest.surface_get(point(left - 1, seed.y)) == Some(&target) {
+            left -= 1;
+        }
+
+        let mut right = seed.x;
+        while right + 1 < size.x && dest.surface_get(point(right + 1, seed.y)) == Some(&target) {
+            right += 1;
+        }
+
+        for x in left..=right {
+            if let Some(value) = dest.surface_get_mut(point(x, seed.y)) {
+                value.clone_from(&new_value);
+            }
+        }
+
+        let rows = [seed.y.checked_sub(1), seed.y.checked_add(1)];
+
+        for y in rows.into_iter().flatten().filter(|&y| y < size.y) {
+            let mut in_span = false;
+
+            for x in left..=right {
+                let matches = dest.surface_get(point(x, y)) == Some(&target);
+
+                if matches && !in_span {
+                    stack.push(point(x, y));
+                }
+
+                in_span = matches;
+            }
+        }
+    }
+}
+
+/// Calls `func` with the center and the offsets of each point of the first octant of the circle.
+#[inline]
+fn for_circle_octants(center: Point, radius: u32, mut func: impl FnMut((i64, i64), i64, i64)) {
+    let center = (center.x as i64, center.y as i64);
+
+    let mut x = radius as i64;
+    let mut y = 0;
+    let mut error = 1 - x;
+
+    while x >= y {
+        (func)(center, x, y);
+
+        y += 1;
+
+        if error < 0 {
+            error += 2 * y + 1;
+        } else {
+            x -= 1;
+            error += 2 * (y - x) + 1;
+        }
+    }
+}
+
+/// Set the values from (x0, y) to (x1, y) (both inclusive) that are inside the surface.
+#[inline]
+fn put_span<T: Clone>(dest: &mut impl SurfaceMut<T>, x0: i64, x1: i64, y: i64, value: &T) {
+    let x0 = x0.max(0);
+
+    if let (Ok(x0), Ok(y), Ok(width)) = (
+        u32::try_from(x0),
+        u32::try_from(y),
+        u32::try_from(x1 - x0 + 1),
+    ) {
+        fill_rect(&mut *dest, point(x0, y), size(width, 1), value.clone());
+    }
+}
+
+/// Set the value at (x, y) if it's inside the surface.
+#[inline]
+fn put<T: Clone>(dest: &mut impl SurfaceMut<T>, x: i64, y: i64, value: &T) {
+    if let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) {
+        if let Some(dest) = dest.surface_get_mut(point(x, y)) {
+            dest.clone_from(value);
+        }
+    }
+}
@@ -1,18 +1,42 @@
-use crate::{point, size, Point, Size, Surface, SurfaceMut};
+#[cfg(feature = "rayon")]
+use crate::GenericSurface;
+use crate::{area, point, size, Point, SignedPoint, Size, Surface, SurfaceMut};
+#[cfg(all(feature = "num-traits", feature = "alloc"))]
+use crate::{signed_point, BorderSurface};
+#[cfg(all(feature = "num-traits", feature = "alloc"))]
+use alloc::vec::Vec;
+use core::{
+    convert::Infallible,
+    hash::{Hash, Hasher},
+    ops::{BitAnd, BitOr, BitXor, Deref},
+};
+#[cfg(feature = "num-traits")]
+use num_traits::{float::FloatCore, SaturatingAdd};
+#[cfg(feature = "rgb")]
+use rgb::RGBA8;
 
 /// Transformations that can be applied when blitting.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Transform {
-    /// Scales the destination.
-    /// Only upscaling is supported.
+    /// Scales the destination up.
     UpScale {
         /// Scale x factor.
         x: u32,
         /// Scale y factor.
         y: u32,
     },
+    /// Scales the destination down.
+    ///
+    /// Uses nearest-neighbor sampling: each destination value is the top-left value of its source block,
+    /// no averaging is done. The trailing partial blocks (if the size isn't divisible by the factors) are dropped.
+    DownScale {
+        /// Scale x factor.
+        x: u32,
+        /// Scale y factor.
+        y: u32,
+    },
 
     /// Rotates the destination 90 degrees clockwise.
     Rotate90Cw,
@@ -25,18 +49,102 @@ pub enum Transform {
     FlipHorizontal,
     /// Flips the destination vertically.
     FlipVertical,
-    /// /// Flips the destination horizontally and vertically.
+    /// Flips the destination horizontally and vertically.
     FlipBoth,
+    /// Transposes the destination (flips it across the main diagonal, swapping x and y).
+    Transpose,
 }
 
 impl Transform {
+    /// Checks whether the transform can be applied, i.e. that its scale factors are non-zero.
     #[inline]
-    #[allow(dead_code)]
-    fn apply((pt, size): (Point, Size), this: &Self) -> (Point, Size) {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Transform::UpScale { x, y } | Transform::DownScale { x, y } => *x != 0 && *y != 0,
+            _ => true,
+        }
+    }
+
+    /// Returns the transform that undoes this one.
+    ///
+    /// [`Transform::UpScale`] is undone by [`Transform::DownScale`] with the same factors,
+    /// but [`Transform::DownScale`] drops values and can't be undone, so `None` is returned for it.
+    #[inline]
+    pub fn inverse(self) -> Option<Transform> {
         use Transform::*;
 
-        let pt = match this {
+        match self {
+            UpScale { x, y } => Some(DownScale { x, y }),
+            DownScale { .. } => None,
+
+            Rotate90Cw => Some(Rotate90Ccw),
+            Rotate90Ccw => Some(Rotate90Cw),
+
+            Rotate180 | FlipHorizontal | FlipVertical | FlipBoth | Transpose => Some(self),
+        }
+    }
+
+    /// Returns the chain of transforms that undoes `transforms`
+    /// (the inverted transforms in reverse order).
+    ///
+    /// Yields `None` for the transforms that can't be undone.
+    #[inline]
+    pub fn invert_chain(
+        transforms: &[Transform],
+    ) -> impl DoubleEndedIterator<Item = Option<Transform>> + '_ {
+        transforms.iter().rev().map(|transform| transform.inverse())
+    }
+
+    /// Collapses a chain of transforms into an equivalent one with at most one rotation,
+    /// one flip and one scale.
+    ///
+    /// Returns `None` if the chain contains [`Transform::DownScale`],
+    /// since it's lossy and can't be moved around the other transforms.
+    pub fn simplify(transforms: &[Transform]) -> Option<SimplifiedTransforms> {
+        use Transform::*;
+
+        let (matrix, scale) = Dihedral::of_chain(transforms)?;
+
+        let mut simplified = SimplifiedTransforms {
+            transforms: [Transform::FlipBoth; 3],
+            len: 0,
+        };
+
+        let dihedral: &[Transform] = match matrix.0 {
+            [1, 0, 0, 1] => &[],
+            [-1, 0, 0, 1] => &[FlipHorizontal],
+            [1, 0, 0, -1] => &[FlipVertical],
+            [-1, 0, 0, -1] => &[Rotate180],
+            [0, -1, 1, 0] => &[Rotate90Cw],
+            [0, 1, -1, 0] => &[Rotate90Ccw],
+            [0, 1, 1, 0] => &[Transpose],
+            [0, -1, -1, 0] => &[Rotate90Cw, FlipVertical],
+            _ => unreachable!(),
+        };
+
+        for &transform in dihedral {
+            simplified.push(transform);
+        }
+
+        if scale != size(1, 1) {
+            simplified.push(UpScale {
+                x: scale.x,
+                y: scale.y,
+            });
+        }
+
+        Some(simplified)
+    }
+
+    /// Returns the position of `pt` after the transform,
+    /// `size` being the size of the surface before the transform.
+    #[inline]
+    pub fn transform_point(&self, pt: Point, size: Size) -> Point {
+        use Transform::*;
+
+        match self {
             UpScale { x, y } => point(pt.x * x, pt.y * y),
+            DownScale { x, y } => point(pt.x / x, pt.y / y),
 
             FlipHorizontal => point(reversed(pt.x, size.x), pt.y),
             FlipVertical => point(pt.x, reversed(pt.y, size.y)),
@@ -45,9 +153,59 @@ impl Transform {
             Rotate90Ccw => point(pt.y, reversed(pt.x, size.x)),
             Rotate90Cw => point(reversed(pt.y, size.y), pt.x),
             Rotate180 => point(reversed(pt.x, size.x), reversed(pt.y, size.y)),
-        };
+            Transpose => point(pt.y, pt.x),
+        }
+    }
+
+    /// Returns the size of a surface after the transform.
+    #[inline]
+    pub fn transform_size(&self, s: Size) -> Size {
+        use Transform::*;
+
+        match self {
+            UpScale { x, y } => size(s.x * x, s.y * y),
+            DownScale { x, y } => size(s.x / x, s.y / y),
+            Rotate90Cw | Rotate90Ccw | Transpose => size(s.y, s.x),
+            _ => s,
+        }
+    }
+
+    /// Returns the position of `pt` after all the transforms (done in order),
+    /// `size` being the size of the surface before the transforms.
+    #[inline]
+    pub fn transform_point_chain(transforms: &[Transform], pt: Point, size: Size) -> Point {
+        transforms.iter().fold((pt, size), Self::apply).0
+    }
+
+    /// Returns the size of a surface after all the transforms (done in order).
+    #[inline]
+    pub fn transform_size_chain(transforms: &[Transform], size: Size) -> Size {
+        transforms.iter().fold(size, Self::apply_size)
+    }
+
+    /// Returns the size of a surface after all the transforms (done in order)
+    /// and the offset to add to its position to keep its center in the same place.
+    ///
+    /// The offset is negative in the dimensions that grow (e.g. the width of a tall sprite rotated by 90 degrees),
+    /// so it's meant to be used with [`blit_at`]. Odd differences are rounded towards the top-left.
+    #[inline]
+    pub fn dest_bounds(transforms: &[Transform], src_size: Size) -> (Size, SignedPoint) {
+        let dest_size = Self::transform_size_chain(transforms, src_size);
+        let correction =
+            |src: u32, dest: u32| (i64::from(src) - i64::from(dest)).div_euclid(2) as i32;
+
+        (
+            dest_size,
+            SignedPoint {
+                x: correction(src_size.x, dest_size.x),
+                y: correction(src_size.y, dest_size.y),
+            },
+        )
+    }
 
-        (pt, Self::apply_size(size, this))
+    #[inline]
+    fn apply((pt, size): (Point, Size), this: &Self) -> (Point, Size) {
+        (this.transform_point(pt, size), this.transform_size(size))
     }
 
     #[inline]
@@ -56,12 +214,14 @@ impl Transform {
 
         let pt = match this {
             UpScale { x, y } => point(pt.x / x, pt.y / y),
+            DownScale { x, y } => point(pt.x * x, pt.y * y),
 
             // unchanged
             FlipHorizontal => point(reversed(pt.x, size.x), pt.y),
             FlipVertical => point(pt.x, reversed(pt.y, size.y)),
             FlipBoth => point(reversed(pt.x, size.x), reversed(pt.y, size.y)),
             Rotate180 => point(reversed(pt.x, size.x), reversed(pt.y, size.y)),
+            Transpose => point(pt.y, pt.x),
 
             // swapped between each other
             Rotate90Cw => point(pt.y, reversed(pt.x, size.x)),
@@ -73,29 +233,190 @@ impl Transform {
 
     #[inline]
     fn apply_size(s: Size, this: &Self) -> Size {
+        this.transform_size(s)
+    }
+
+    #[inline]
+    fn unapply_size(s: Size, this: &Self) -> Size {
         use Transform::*;
 
         match this {
-            UpScale { x, y } => size(s.x * x, s.y * y),
-            Rotate90Cw | Rotate90Ccw => size(s.y, s.x),
+            UpScale { x, y } => size(s.x / x, s.y / y),
+            DownScale { x, y } => size(s.x * x, s.y * y),
+            Rotate90Cw | Rotate90Ccw | Transpose => size(s.y, s.x),
             _ => s,
         }
     }
+}
+
+/// A simplified chain of transforms, returned by [`Transform::simplify`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimplifiedTransforms {
+    transforms: [Transform; 3],
+    len: usize,
+}
 
+impl SimplifiedTransforms {
     #[inline]
-    fn unapply_size(s: Size, this: &Self) -> Size {
+    fn push(&mut self, transform: Transform) {
+        self.transforms[self.len] = transform;
+        self.len += 1;
+    }
+}
+
+impl Deref for SimplifiedTransforms {
+    type Target = [Transform];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.transforms[..self.len]
+    }
+}
+
+/// A chain of up to [`TransformList::CAPACITY`] transforms stored inline, so it can be built without allocating.
+///
+/// Dereferences to `[Transform]`, so it can be passed to the blit functions directly.
+/// [`TransformList::push`] panics when the list is full, use [`TransformList::try_push`] to handle that instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransformList {
+    transforms: [Transform; TransformList::CAPACITY],
+    len: usize,
+}
+
+impl TransformList {
+    /// The maximum number of transforms in a list.
+    pub const CAPACITY: usize = 8;
+
+    /// Construct an empty list.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            transforms: [Transform::FlipBoth; Self::CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Append a transform to the end of the list.
+    ///
+    /// Panics if the list already holds [`TransformList::CAPACITY`] transforms.
+    #[inline]
+    #[track_caller]
+    pub fn push(&mut self, transform: Transform) {
+        if self.try_push(transform).is_err() {
+            panic!(
+                "transform list is full (capacity is {})",
+                TransformList::CAPACITY
+            );
+        }
+    }
+
+    /// Append a transform to the end of the list.
+    ///
+    /// Returns the transform back if the list is full.
+    #[inline]
+    pub fn try_push(&mut self, transform: Transform) -> Result<(), Transform> {
+        match self.transforms.get_mut(self.len) {
+            Some(slot) => {
+                *slot = transform;
+                self.len += 1;
+                Ok(())
+            }
+            None => Err(transform),
+        }
+    }
+
+    /// Remove the last transform and return it, `None` if the list is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<Transform> {
+        self.len = self.len.checked_sub(1)?;
+
+        Some(core::mem::replace(
+            &mut self.transforms[self.len],
+            Transform::FlipBoth,
+        ))
+    }
+
+    /// Remove all the transforms.
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for TransformList {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for TransformList {
+    type Target = [Transform];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.transforms[..self.len]
+    }
+}
+
+/// 2x2 integer matrix of one of the 8 symmetries of a rectangle (in row-major order).
+#[derive(Clone, Copy)]
+struct Dihedral([i8; 4]);
+
+impl Dihedral {
+    const IDENTITY: Self = Self([1, 0, 0, 1]);
+
+    /// Collapses a chain without [`Transform::DownScale`] into a matrix that maps
+    /// centered source coordinates to centered destination coordinates and the scale applied after it.
+    fn of_chain(transforms: &[Transform]) -> Option<(Self, Size)> {
         use Transform::*;
 
-        match this {
-            UpScale { x, y } => size(s.x / x, s.y / y),
-            Rotate90Cw | Rotate90Ccw => size(s.y, s.x),
-            _ => s,
+        let mut matrix = Self::IDENTITY;
+        let mut scale = size(1, 1);
+
+        for transform in transforms {
+            let step = match transform {
+                UpScale { x, y } => {
+                    scale = size(scale.x * x, scale.y * y);
+                    continue;
+                }
+                DownScale { .. } => return None,
+
+                FlipHorizontal => Self([-1, 0, 0, 1]),
+                FlipVertical => Self([1, 0, 0, -1]),
+                FlipBoth | Rotate180 => Self([-1, 0, 0, -1]),
+                Rotate90Cw => Self([0, -1, 1, 0]),
+                Rotate90Ccw => Self([0, 1, -1, 0]),
+                Transpose => Self([0, 1, 1, 0]),
+            };
+
+            if step.swaps_axes() {
+                scale = size(scale.y, scale.x);
+            }
+
+            matrix = step.after(matrix);
         }
+
+        Some((matrix, scale))
+    }
+
+    #[inline]
+    fn swaps_axes(self) -> bool {
+        self.0[0] == 0
+    }
+
+    /// Composition of `prev` followed by `self`.
+    #[inline]
+    fn after(self, prev: Self) -> Self {
+        let [a, b, c, d] = self.0;
+        let [e, f, g, h] = prev.0;
+
+        Self([a * e + b * g, a * f + b * h, c * e + d * g, c * f + d * h])
     }
 }
 
 #[inline]
-fn reversed(coord: u32, size: u32) -> u32 {
+pub(crate) fn reversed(coord: u32, size: u32) -> u32 {
     size.saturating_sub(coord).saturating_sub(1)
 }
 
@@ -104,81 +425,1481 @@ fn reversed(coord: u32, size: u32) -> u32 {
 /// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
 /// `f` is called for each pair of values, the last argument is the value's source position.
 /// The transforms are done in order.
+#[inline]
 pub fn blit_with<D, S>(
-    mut dest: impl SurfaceMut<D>,
+    dest: impl SurfaceMut<D>,
     src: impl Surface<S>,
     transforms: &[Transform],
     mut func: impl FnMut(&mut D, &S, Point),
 ) {
-    let copy_size = src.surface_size();
-    let transformed_copy_size = transforms.iter().fold(copy_size, Transform::apply_size);
-
-    for iy in 0..transformed_copy_size.y {
-        for ix in 0..transformed_copy_size.x {
-            let dest_val_pos = point(ix, iy);
-
-            let dest = if let Some(dest) = dest.surface_get_mut(dest_val_pos) {
-                dest
-            } else {
-                continue;
-            };
-
-            let (src_val_pos, _untransformed_copy_size) = transforms
-                .iter()
-                .rev()
-                .fold((point(ix, iy), transformed_copy_size), Transform::unapply);
+    blit_with_positions(dest, src, transforms, |dest, src, _dest_pos, src_pos| {
+        (func)(dest, src, src_pos);
+    });
+}
 
-            let src = if let Some(src) = src.surface_get(src_val_pos) {
-                src
-            } else {
-                continue;
-            };
+/// Blit part of one surface to another (generalized function).
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// `f` is called for each pair of values, the last two arguments are the value's destination and source positions.
+/// The destination position is the one on the (possibly offset) destination surface itself.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_with_positions<D, S>(
+    dest: impl SurfaceMut<D>,
+    src: impl Surface<S>,
+    transforms: &[Transform],
+    mut func: impl FnMut(&mut D, &S, Point, Point),
+) {
+    let result: Result<(), Infallible> = blit_engine(
+        dest,
+        src,
+        transforms,
+        point(0, 0),
+        u32::MAX,
+        |dest, src, dest_pos, src_pos| {
+            (func)(dest, src, dest_pos, src_pos);
+            Ok(())
+        },
+    );
 
-            (func)(dest, src, src_val_pos);
-        }
+    match result {
+        Ok(()) => {}
+        Err(never) => match never {},
     }
 }
 
-/// Blit part of one surface to another, cloning the values.
+/// Blit part of one surface to another (generalized fallible function).
 ///
 /// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// `f` is called for each pair of values, the last argument is the value's source position.
 /// The transforms are done in order.
+///
+/// Stops at the first error returned by `f` and returns it.
+/// The values written before that are left as is.
 #[inline]
-pub fn blit<T: Clone>(dest: impl SurfaceMut<T>, src: impl Surface<T>, transforms: &[Transform]) {
-    blit_with(dest, src, transforms, |dest, src, _| {
-        dest.clone_from(src);
-    });
+pub fn try_blit_with<D, S, E>(
+    dest: impl SurfaceMut<D>,
+    src: impl Surface<S>,
+    transforms: &[Transform],
+    mut func: impl FnMut(&mut D, &S, Point) -> Result<(), E>,
+) -> Result<(), E> {
+    blit_engine(
+        dest,
+        src,
+        transforms,
+        point(0, 0),
+        u32::MAX,
+        |dest, src, _dest_pos, src_pos| (func)(dest, src, src_pos),
+    )
 }
 
-/// Blit part of one surface to another, ignoring the `mask` values.
+/// Blit part of one surface to another (generalized function), counting the written values.
 ///
 /// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// `f` is called for each pair of values, the last argument is the value's source position.
 /// The transforms are done in order.
+///
+/// Returns the number of times `f` was called,
+/// i.e. the number of positions present on both the destination and the source.
 #[inline]
-pub fn blit_masked<T: Clone + PartialEq>(
-    dest: impl SurfaceMut<T>,
-    src: impl Surface<T>,
+pub fn blit_with_count<D, S>(
+    dest: impl SurfaceMut<D>,
+    src: impl Surface<S>,
     transforms: &[Transform],
-    mask: &T,
-) {
-    blit_with(dest, src, transforms, |dest, src, _| {
-        if src != mask {
-            dest.clone_from(src);
-        }
+    mut func: impl FnMut(&mut D, &S, Point),
+) -> usize {
+    let mut count = 0;
+
+    blit_with(dest, src, transforms, |dest, src, src_pos| {
+        count += 1;
+        (func)(dest, src, src_pos);
     });
+
+    count
 }
 
-/// Blit part of one surface to another, converting the values.
+/// Blit two surfaces at once to another one (generalized function).
 ///
+/// Both sources are clipped to the smaller of their sizes and transformed the same way.
+/// `f` is called for each destination value and the values of both sources at the same position,
+/// the last argument is that source position.
 /// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
 /// The transforms are done in order.
 #[inline]
-pub fn blit_convert<D: From<S>, S: Clone>(
+pub fn blit_zip_with<D, A, B>(
     dest: impl SurfaceMut<D>,
-    src: impl Surface<S>,
+    src_a: impl Surface<A>,
+    src_b: impl Surface<B>,
     transforms: &[Transform],
+    mut func: impl FnMut(&mut D, &A, &B, Point),
 ) {
-    blit_with(dest, src, transforms, |dest, src, _| {
-        *dest = D::from(src.clone());
+    let a_size = src_a.surface_size();
+    let b_size = src_b.surface_size();
+    let src_a = src_a.into_sub_surface(
+        point(0, 0),
+        size(a_size.x.min(b_size.x), a_size.y.min(b_size.y)),
+    );
+
+    blit_with(dest, src_a, transforms, |dest, a, src_pos| {
+        if let Some(b) = src_b.surface_get(src_pos) {
+            (func)(dest, a, b, src_pos);
+        }
     });
 }
+
+/// Maps a position on the transformed surface back to the source surface.
+#[inline]
+fn source_point(
+    transforms: &[Transform],
+    pt: Point,
+    copy_size: Size,
+    transformed_copy_size: Size,
+) -> Point {
+    let (pt, _untransformed_copy_size) = transforms.iter().enumerate().rev().fold(
+        (pt, transformed_copy_size),
+        |(pt, size), (i, transform)| {
+            let (pt, size) = Transform::unapply((pt, size), transform);
+
+            match transform {
+                // scaling down drops the remainders, so the size before it has to be recomputed
+                Transform::DownScale { .. } => (
+                    pt,
+                    Transform::transform_size_chain(&transforms[..i], copy_size),
+                ),
+                _ => (pt, size),
+            }
+        },
+    );
+
+    pt
+}
+
+/// Precomputed mapping from destination to source positions, so that
+/// the blit doesn't have to fold over the transforms for every value.
+///
+/// Only exists for chains without [`Transform::DownScale`]: the source position is then
+/// an affine function of the destination position divided by the scale.
+#[derive(Clone, Copy, Debug)]
+struct SourceMap {
+    scale: Size,
+    origin: [i64; 2],
+    step_x: [i64; 2],
+    step_y: [i64; 2],
+}
+
+impl SourceMap {
+    fn new(transforms: &[Transform], copy_size: Size) -> Option<Self> {
+        let (matrix, scale) = Dihedral::of_chain(transforms)?;
+        let [a, b, c, d] = matrix.0.map(i64::from);
+
+        // size before the scale
+        let unscaled = if matrix.swaps_axes() {
+            size(copy_size.y, copy_size.x)
+        } else {
+            copy_size
+        };
+        let (w, h) = (i64::from(copy_size.x), i64::from(copy_size.y));
+        let (nx, ny) = (i64::from(unscaled.x), i64::from(unscaled.y));
+
+        // the inverse of the matrix is its transpose;
+        // centered coordinates are `2 * v - (len - 1)`, which cancels out into these offsets
+        Some(Self {
+            scale,
+            origin: [
+                (a * (1 - nx) + c * (1 - ny) + w - 1) / 2,
+                (b * (1 - nx) + d * (1 - ny) + h - 1) / 2,
+            ],
+            step_x: [a, b],
+            step_y: [c, d],
+        })
+    }
+
+    /// Source position of the start of the destination row `y`.
+    #[inline]
+    fn row(&self, y: u32) -> [i64; 2] {
+        let qy = i64::from(y / self.scale.y);
+
+        [
+            self.origin[0] + self.step_y[0] * qy,
+            self.origin[1] + self.step_y[1] * qy,
+        ]
+    }
+
+    /// Source position of (x, y), `row` being the result of [`SourceMap::row`] for y.
+    #[inline]
+    fn point(&self, row: [i64; 2], x: u32) -> Point {
+        let qx = i64::from(x / self.scale.x);
+
+        point(
+            (row[0] + self.step_x[0] * qx) as u32,
+            (row[1] + self.step_x[1] * qx) as u32,
+        )
+    }
+}
+
+/// Iterator over the destination and source positions of a blit, returned by [`transformed_coords`].
+#[derive(Clone, Debug)]
+pub struct TransformedCoords<'a> {
+    transforms: &'a [Transform],
+    simplified: Option<SimplifiedTransforms>,
+    copy_size: Size,
+    transformed_copy_size: Size,
+    source_map: Option<SourceMap>,
+    next: Point,
+    row: Option<[i64; 2]>,
+}
+
+impl<'a> TransformedCoords<'a> {
+    fn new(transforms: &'a [Transform], copy_size: Size) -> Self {
+        let simplified = Transform::simplify(transforms);
+        let chain = simplified.as_deref().unwrap_or(transforms);
+
+        Self {
+            transforms,
+            copy_size,
+            transformed_copy_size: Transform::transform_size_chain(chain, copy_size),
+            source_map: SourceMap::new(chain, copy_size),
+            simplified,
+            next: point(0, 0),
+            row: None,
+        }
+    }
+
+    /// Number of positions left, if it fits in `usize`.
+    #[inline]
+    fn remaining(&self) -> Option<usize> {
+        let size = self.transformed_copy_size;
+
+        if size.x == 0 || self.next.y >= size.y {
+            return Some(0);
+        }
+
+        let full_rows = u64::from(size.y - self.next.y - 1) * u64::from(size.x);
+        usize::try_from(full_rows + u64::from(size.x - self.next.x)).ok()
+    }
+}
+
+impl Iterator for TransformedCoords<'_> {
+    type Item = (Point, Point);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = self.transformed_copy_size;
+        let pt = self.next;
+
+        if size.x == 0 || pt.y >= size.y {
+            return None;
+        }
+
+        let src_pos = match self.source_map {
+            Some(map) => map.point(*self.row.get_or_insert_with(|| map.row(pt.y)), pt.x),
+            None => source_point(
+                self.simplified.as_deref().unwrap_or(self.transforms),
+                pt,
+                self.copy_size,
+                size,
+            ),
+        };
+
+        if pt.x + 1 < size.x {
+            self.next.x += 1;
+        } else {
+            self.next = point(0, pt.y + 1);
+            self.row = None;
+        }
+
+        Some((pt, src_pos))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining() {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+/// Returns an iterator over the `(dest_pos, src_pos)` pairs of blitting a source of `src_size` with `transforms`,
+/// in the order the blit functions visit them (row-major on the destination).
+///
+/// Every position of the transformed source is yielded, a blit then skips the pairs outside of the surfaces.
+/// It can be used to write custom blits, or just to see where the transforms move the values.
+#[inline]
+pub fn transformed_coords(src_size: Size, transforms: &[Transform]) -> TransformedCoords<'_> {
+    TransformedCoords::new(transforms, src_size)
+}
+
+fn blit_engine<D, S, E>(
+    mut dest: impl SurfaceMut<D>,
+    src: impl Surface<S>,
+    transforms: &[Transform],
+    skip: Point,
+    max_rows: u32,
+    mut func: impl FnMut(&mut D, &S, Point, Point) -> Result<(), E>,
+) -> Result<(), E> {
+    for transform in transforms {
+        debug_assert!(
+            transform.is_valid(),
+            "invalid transform {transform:?}: scale factors must be non-zero"
+        );
+    }
+
+    let simplified = Transform::simplify(transforms);
+    let transforms = simplified.as_deref().unwrap_or(transforms);
+
+    let copy_size = src.surface_size();
+    let transformed_copy_size = Transform::transform_size_chain(transforms, copy_size);
+    let source_map = SourceMap::new(transforms, copy_size);
+
+    // `skip` is the top-left corner of the transformed source that's placed at the destination origin,
+    // and at most `max_rows` of its rows are visited; the order is the same as the one of `TransformedCoords`
+    let end_y = transformed_copy_size.y.min(skip.y.saturating_add(max_rows));
+
+    for iy in skip.y..end_y {
+        let row = source_map.map(|map| (map, map.row(iy)));
+
+        for ix in skip.x..transformed_copy_size.x {
+            let dest_val_pos = point(ix - skip.x, iy - skip.y);
+
+            let dest = if let Some(dest) = dest.surface_get_mut(dest_val_pos) {
+                dest
+            } else {
+                continue;
+            };
+
+            let src_val_pos = match row {
+                Some((map, row)) => map.point(row, ix),
+                None => source_point(transforms, point(ix, iy), copy_size, transformed_copy_size),
+            };
+
+            let src = if let Some(src) = src.surface_get(src_val_pos) {
+                src
+            } else {
+                continue;
+            };
+
+            (func)(dest, src, dest_val_pos, src_val_pos)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Blit a surface to another with the top-left corner of the (transformed) source at `dest_pos`, cloning the values.
+///
+/// `dest_pos` may be negative, the part of the source that falls outside of the destination is skipped.
+/// This is useful to slide sprites in from the top or left edge, which `offset_surface` can't express.
+/// The transforms are done in order, before positioning.
+#[inline]
+pub fn blit_at<T: Clone>(
+    mut dest: impl SurfaceMut<T>,
+    dest_pos: SignedPoint,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+) {
+    let offset = point(dest_pos.x.max(0) as u32, dest_pos.y.max(0) as u32);
+    let skip = point(
+        dest_pos.x.min(0).unsigned_abs(),
+        dest_pos.y.min(0).unsigned_abs(),
+    );
+
+    let result: Result<(), Infallible> = blit_engine(
+        dest.offset_surface_mut(offset),
+        src,
+        transforms,
+        skip,
+        u32::MAX,
+        |dest, src, _, _| {
+            dest.clone_from(src);
+            Ok(())
+        },
+    );
+
+    match result {
+        Ok(()) => {}
+        Err(never) => match never {},
+    }
+}
+
+/// Blit a surface to the whole destination, starting `scroll` values into the (transformed) source
+/// and wrapping around its edges, cloning the values.
+///
+/// Every destination value at (x, y) is the transformed source value at
+/// ((x + scroll.x) % width, (y + scroll.y) % height), so the source is repeated to fill the destination.
+/// This is like blitting an offset [`WrapSurface`](crate::WrapSurface) sized to the destination, e.g. for parallax backgrounds.
+/// The transforms are done in order, before scrolling.
+pub fn blit_scrolled<T: Clone>(
+    mut dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    scroll: Point,
+    transforms: &[Transform],
+) {
+    let simplified = Transform::simplify(transforms);
+    let transforms = simplified.as_deref().unwrap_or(transforms);
+
+    let copy_size = src.surface_size();
+    let transformed_copy_size = Transform::transform_size_chain(transforms, copy_size);
+
+    if transformed_copy_size.x == 0 || transformed_copy_size.y == 0 {
+        return;
+    }
+
+    let dest_size = dest.surface_size();
+    let start = point(
+        scroll.x % transformed_copy_size.x,
+        scroll.y % transformed_copy_size.y,
+    );
+
+    for y in 0..dest_size.y {
+        let sy = ((start.y as u64 + y as u64) % transformed_copy_size.y as u64) as u32;
+
+        for x in 0..dest_size.x {
+            let sx = ((start.x as u64 + x as u64) % transformed_copy_size.x as u64) as u32;
+            let src_pos = source_point(transforms, point(sx, sy), copy_size, transformed_copy_size);
+
+            if let (Some(dest), Some(src)) =
+                (dest.surface_get_mut(point(x, y)), src.surface_get(src_pos))
+            {
+                dest.clone_from(src);
+            }
+        }
+    }
+}
+
+/// How much of the source was written by [`blit_checked`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Coverage {
+    /// Every value of the (transformed) source was written.
+    Full,
+    /// Only some of the values were written, the rest fell outside of the destination.
+    Partial {
+        /// Number of written values.
+        written: usize,
+    },
+}
+
+/// Error returned by [`blit_checked`] when no values were written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Clipped;
+
+impl core::fmt::Display for Clipped {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("the blit source is entirely outside of the destination")
+    }
+}
+
+impl core::error::Error for Clipped {}
+
+/// Blit part of one surface to another, cloning the values and reporting how much of the source was written.
+///
+/// Returns `Err(Clipped)` if nothing was written (including when the source is empty),
+/// which usually means the source was positioned outside of the destination by mistake.
+/// Unlike [`blit`], it always copies value by value.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_checked<T: Clone>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+) -> Result<Coverage, Clipped> {
+    let expected = Transform::transform_size_chain(transforms, src.surface_size());
+
+    let written = blit_with_count(dest, src, transforms, |dest, src, _| {
+        dest.clone_from(src);
+    });
+
+    if written == 0 {
+        Err(Clipped)
+    } else if written as u64 == area(expected) {
+        Ok(Coverage::Full)
+    } else {
+        Ok(Coverage::Partial { written })
+    }
+}
+
+/// Blit part of one surface to another, cloning the values.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit<T: Clone>(
+    mut dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+) {
+    let untransformed = Transform::simplify(transforms).is_some_and(|chain| chain.is_empty());
+
+    if untransformed && dest.surface_row_mut(0).is_some() && src.surface_row(0).is_some() {
+        blit_rows(dest, src);
+    } else {
+        blit_with(dest, src, transforms, |dest, src, _| {
+            dest.clone_from(src);
+        });
+    }
+}
+
+/// Blit a sequence of sources to one destination, cloning the values.
+///
+/// Each command is the position of the source on the destination, the source and its transforms.
+/// The commands are done in order (so the later ones are drawn over the earlier ones),
+/// each one clipped to the destination independently.
+#[inline]
+pub fn blit_all<'a, T: Clone, S: Surface<T>>(
+    mut dest: impl SurfaceMut<T>,
+    commands: impl IntoIterator<Item = (Point, S, &'a [Transform])>,
+) {
+    for (position, src, transforms) in commands {
+        blit(dest.offset_surface_mut(position), src, transforms);
+    }
+}
+
+/// Blit part of one surface to another on several threads, cloning the values.
+///
+/// The destination is split into bands of rows, one per thread of the current [`rayon`] pool,
+/// each one blitted from the matching rows of the transformed source. The result is the same as the one of [`blit`],
+/// but it's only faster for big surfaces: for small ones the threads cost more than they save.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area (of the source).
+/// The transforms are done in order.
+#[cfg(feature = "rayon")]
+pub fn blit_par<Slice, T>(
+    dest: &mut GenericSurface<Slice, T>,
+    src: impl Surface<T> + Sync,
+    transforms: &[Transform],
+) where
+    Slice: AsRef<[T]> + AsMut<[T]>,
+    T: Clone + Send + Sync,
+{
+    let transformed_size = Transform::transform_size_chain(transforms, src.surface_size());
+    let rows = dest.surface_size().y.min(transformed_size.y);
+    let band = rows
+        .div_ceil(rayon::current_num_threads().max(1) as u32)
+        .max(1);
+
+    if rows == 0 {
+        return;
+    }
+
+    let (dest, _) = dest.split_at_row(rows);
+    blit_bands(dest, 0, band, &src, transforms);
+}
+
+/// Blits `dest` from the transformed source starting at row `first_row`,
+/// recursively splitting it into halves until they are at most `band` rows high.
+#[cfg(feature = "rayon")]
+fn blit_bands<T: Clone + Send + Sync>(
+    mut dest: GenericSurface<&mut [T], T>,
+    first_row: u32,
+    band: u32,
+    src: &(impl Surface<T> + Sync),
+    transforms: &[Transform],
+) {
+    let rows = dest.surface_size().y;
+
+    if rows > band {
+        // keep the split on a band boundary, so that all bands (but the last one) are full
+        let mid = rows.div_ceil(band) / 2 * band;
+        let (top, bottom) = dest.split_at_row(mid);
+
+        rayon::join(
+            || blit_bands(top, first_row, band, src, transforms),
+            || blit_bands(bottom, first_row + mid, band, src, transforms),
+        );
+    } else if Transform::simplify(transforms).is_some_and(|chain| chain.is_empty()) {
+        // copies whole rows if the source provides them
+        blit(dest, src.offset_surface(point(0, first_row)), &[]);
+    } else {
+        let result: Result<(), Infallible> = blit_engine(
+            dest,
+            src,
+            transforms,
+            point(0, first_row),
+            rows,
+            |dest, src, _, _| {
+                dest.clone_from(src);
+                Ok(())
+            },
+        );
+
+        match result {
+            Ok(()) => {}
+            Err(never) => match never {},
+        }
+    }
+}
+
+/// Copy all of `src` to `dest` of the same size, without transforms or clipping.
+///
+/// Meant for hot loops where the sizes are already known to match.
+/// Surfaces that store their rows contiguously (like [`GenericSurface`](crate::GenericSurface))
+/// are copied with whole-row slice copies.
+///
+/// # Panics
+///
+/// Panics in debug builds if the sizes differ.
+/// In release builds mismatched sizes are not checked: the overlapping top-left area
+/// (the smaller of the two widths and of the two heights) is copied and the rest of `dest` is left as is.
+#[track_caller]
+pub fn blit_exact<T: Clone>(dest: impl SurfaceMut<T>, src: impl Surface<T>) {
+    debug_assert_eq!(
+        dest.surface_size(),
+        src.surface_size(),
+        "blit_exact called with surfaces of different sizes"
+    );
+
+    blit_rows(dest, src);
+}
+
+/// Copies the overlapping rows of two untransformed surfaces slice by slice.
+fn blit_rows<T: Clone>(mut dest: impl SurfaceMut<T>, src: impl Surface<T>) {
+    let dest_size = dest.surface_size();
+    let src_size = src.surface_size();
+    let copy_size = size(dest_size.x.min(src_size.x), dest_size.y.min(src_size.y));
+
+    for y in 0..copy_size.y {
+        match (dest.surface_row_mut(y), src.surface_row(y)) {
+            (Some(dest_row), Some(src_row)) => {
+                let width = copy_size.x as usize;
+                dest_row[..width].clone_from_slice(&src_row[..width]);
+            }
+            _ => {
+                for x in 0..copy_size.x {
+                    if let (Some(dest), Some(src)) = (
+                        dest.surface_get_mut(point(x, y)),
+                        src.surface_get(point(x, y)),
+                    ) {
+                        dest.clone_from(src);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Blit part of one surface to another, ignoring the `mask` values.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_masked<T: Clone + PartialEq>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+    mask: &T,
+) {
+    blit_masked_by(dest, src, transforms, |src| src != mask);
+}
+
+/// Blit part of one surface to another, only copying the values for which `predicate` returns `true`.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_masked_by<T: Clone>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+    predicate: impl Fn(&T) -> bool,
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        if (predicate)(src) {
+            dest.clone_from(src);
+        }
+    });
+}
+
+/// Blit part of one surface to another, treating the values equal to `key` as transparent (color keying).
+///
+/// This is the same as [`blit_masked`]. Match the whole value, e.g. `RGBA8::new(255, 0, 255, 0)`
+/// only skips magenta values that are also fully transparent.
+/// Use [`blit_color_key_tolerance`] for sprites with lossy compression artifacts.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_color_key<T: Clone + PartialEq>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+    key: T,
+) {
+    blit_masked(dest, src, transforms, &key);
+}
+
+/// Blit part of one surface to another, treating the values close to `key` as transparent (color keying).
+///
+/// A value is skipped if each of its channels (alpha included) differs from the one in `key` by at most `tolerance`,
+/// so a `tolerance` of 0 is the same as [`blit_color_key`].
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[cfg(feature = "rgb")]
+#[inline]
+pub fn blit_color_key_tolerance(
+    dest: impl SurfaceMut<RGBA8>,
+    src: impl Surface<RGBA8>,
+    transforms: &[Transform],
+    key: RGBA8,
+    tolerance: u8,
+) {
+    blit_masked_by(dest, src, transforms, |src| {
+        src.r.abs_diff(key.r) > tolerance
+            || src.g.abs_diff(key.g) > tolerance
+            || src.b.abs_diff(key.b) > tolerance
+            || src.a.abs_diff(key.a) > tolerance
+    });
+}
+
+/// Blit part of one surface to another, converting the values.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_convert<D: From<S>, S: Clone>(
+    dest: impl SurfaceMut<D>,
+    src: impl Surface<S>,
+    transforms: &[Transform],
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        *dest = D::from(src.clone());
+    });
+}
+
+/// Blit the whole source surface to the `dest_size` rectangle at the start of the destination,
+/// resizing it with nearest-neighbor sampling.
+///
+/// Unlike [`Transform::UpScale`], the ratio between the sizes doesn't have to be an integer:
+/// the destination value at (x, y) is taken from `(x * src_width / dest_width, y * src_height / dest_height)`.
+/// Use `offset_surface` to move the rectangle.
+pub fn blit_scaled<T: Clone>(mut dest: impl SurfaceMut<T>, src: impl Surface<T>, dest_size: Size) {
+    let src_size = src.surface_size();
+    let dest_surface_size = dest.surface_size();
+
+    for y in 0..dest_size.y.min(dest_surface_size.y) {
+        let src_y = scaled_coord(y, src_size.y, dest_size.y);
+
+        for x in 0..dest_size.x.min(dest_surface_size.x) {
+            let src_x = scaled_coord(x, src_size.x, dest_size.x);
+
+            if let (Some(dest), Some(src)) = (
+                dest.surface_get_mut(point(x, y)),
+                src.surface_get(point(src_x, src_y)),
+            ) {
+                dest.clone_from(src);
+            }
+        }
+    }
+}
+
+/// `coord * src_len / dest_len` without overflowing.
+#[inline]
+fn scaled_coord(coord: u32, src_len: u32, dest_len: u32) -> u32 {
+    (u64::from(coord) * u64::from(src_len) / u64::from(dest_len)) as u32
+}
+
+/// Source read with a step, only used by [`blit_strided`].
+struct StridedSource<S> {
+    surface: S,
+    step: Size,
+}
+
+impl<T, S: Surface<T>> Surface<T> for StridedSource<S> {
+    #[inline]
+    fn surface_size(&self) -> Size {
+        let src_size = self.surface.surface_size();
+
+        size(
+            src_size.x.div_ceil(self.step.x),
+            src_size.y.div_ceil(self.step.y),
+        )
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        self.surface.surface_get(point(
+            pt.x.checked_mul(self.step.x)?,
+            pt.y.checked_mul(self.step.y)?,
+        ))
+    }
+}
+
+/// Blit every `step.x`-th value of every `step.y`-th row of the source to the destination, cloning the values.
+///
+/// The destination value at (x, y) is the source value at `(x * step.x, y * step.y)` (before the transforms),
+/// so the source is decimated without any averaging, e.g. for quick previews.
+/// Unlike [`Transform::DownScale`], the trailing partial blocks are kept: a source 5 values wide with `step.x == 2`
+/// is sampled at 0, 2 and 4. Nothing is done if either step is zero.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order, after sampling.
+#[inline]
+pub fn blit_strided<T: Clone>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    step: Size,
+    transforms: &[Transform],
+) {
+    if step.x == 0 || step.y == 0 {
+        return;
+    }
+
+    blit_with(
+        dest,
+        StridedSource { surface: src, step },
+        transforms,
+        |dest, src, _| {
+            dest.clone_from(src);
+        },
+    );
+}
+
+/// Blit part of one surface to another, combining the values with bitwise OR.
+///
+/// The destination values are read as well as written.
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_bitor<T: BitOr<Output = T> + Copy>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        *dest = *dest | *src;
+    });
+}
+
+/// Blit part of one surface to another, combining the values with bitwise AND.
+///
+/// The destination values are read as well as written.
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_bitand<T: BitAnd<Output = T> + Copy>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        *dest = *dest & *src;
+    });
+}
+
+/// Blit part of one surface to another, combining the values with bitwise XOR.
+///
+/// The destination values are read as well as written.
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_bitxor<T: BitXor<Output = T> + Copy>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        *dest = *dest ^ *src;
+    });
+}
+
+/// Blit part of one surface to another, adding the source values to the destination values with saturation.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[cfg(feature = "num-traits")]
+#[inline]
+pub fn blit_saturating_add<T: SaturatingAdd>(
+    dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    transforms: &[Transform],
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        *dest = dest.saturating_add(src);
+    });
+}
+
+/// Check whether two surfaces have the same size and values, regardless of how they store them.
+///
+/// Unlike comparing with `==`, this ignores the surface types and the row padding,
+/// so e.g. a [`SubSurface`](crate::SubSurface) can be compared to a [`GenericSurface`](crate::GenericSurface).
+/// The rows are compared at once if both surfaces provide them.
+pub fn surfaces_eq<T: PartialEq>(a: impl Surface<T>, b: impl Surface<T>) -> bool {
+    let size = a.surface_size();
+
+    if size != b.surface_size() {
+        return false;
+    }
+
+    (0..size.y).all(|y| match (a.surface_row(y), b.surface_row(y)) {
+        (Some(a_row), Some(b_row)) => a_row == b_row,
+        _ => (0..size.x).all(|x| a.surface_get(point(x, y)) == b.surface_get(point(x, y))),
+    })
+}
+
+/// Feed the size and the values (in row-major order) of the surface to `hasher`.
+///
+/// The result only depends on the contents, not on the surface type or the row padding,
+/// so surfaces that are equal according to [`surfaces_eq`] hash the same, e.g. for caching blit results.
+pub fn surface_hash<T: Hash>(surface: impl Surface<T>, hasher: &mut impl Hasher) {
+    let size = surface.surface_size();
+
+    size.x.hash(hasher);
+    size.y.hash(hasher);
+
+    for y in 0..size.y {
+        match surface.surface_row(y) {
+            Some(row) => row.iter().for_each(|value| value.hash(hasher)),
+            None => {
+                for x in 0..size.x {
+                    if let Some(value) = surface.surface_get(point(x, y)) {
+                        value.hash(hasher);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Hash the surface with [`surface_hash`] and the standard library's default hasher.
+///
+/// The hash is the same between runs of a program, but it may change between Rust versions,
+/// so it shouldn't be persisted.
+#[cfg(feature = "std")]
+#[inline]
+pub fn surface_hash_u64<T: Hash>(surface: impl Surface<T>) -> u64 {
+    let mut hasher = std::hash::DefaultHasher::new();
+    surface_hash(surface, &mut hasher);
+    hasher.finish()
+}
+
+/// Find the smallest rectangle (offset and size) that contains all the positions where the surfaces differ.
+///
+/// If the sizes differ, the positions that only one of the surfaces has count as different.
+/// Returns `None` if the surfaces are equal.
+pub fn diff_bounds<T: PartialEq>(a: impl Surface<T>, b: impl Surface<T>) -> Option<(Point, Size)> {
+    let a_size = a.surface_size();
+    let b_size = b.surface_size();
+    let area = size(a_size.x.max(b_size.x), a_size.y.max(b_size.y));
+
+    let mut bounds: Option<(Point, Point)> = None;
+
+    for y in 0..area.y {
+        for x in 0..area.x {
+            let pt = point(x, y);
+
+            if a.surface_get(pt) != b.surface_get(pt) {
+                let (min, max) = bounds.get_or_insert((pt, pt));
+
+                min.x = min.x.min(x);
+                min.y = min.y.min(y);
+                max.x = max.x.max(x);
+                max.y = max.y.max(y);
+            }
+        }
+    }
+
+    bounds.map(|(min, max)| (min, size(max.x - min.x + 1, max.y - min.y + 1)))
+}
+
+/// Values that can be linearly interpolated, used by [`blit_bilinear`].
+#[cfg(feature = "num-traits")]
+pub trait Lerp: Sized {
+    /// Interpolate between `a` (at `t == 0.0`) and `b` (at `t == 1.0`).
+    ///
+    /// Integers are rounded to the nearest value.
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self;
+}
+
+#[cfg(feature = "num-traits")]
+macro_rules! impl_lerp {
+    (int $($ty:ty),*) => {$(
+        impl Lerp for $ty {
+            #[inline]
+            fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+                FloatCore::round(*a as f32 + (*b as f32 - *a as f32) * t) as $ty
+            }
+        }
+    )*};
+    (float $($ty:ty),*) => {$(
+        impl Lerp for $ty {
+            #[inline]
+            fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+                a + (b - a) * t as $ty
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "num-traits")]
+impl_lerp!(int u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+#[cfg(feature = "num-traits")]
+impl_lerp!(float f32, f64);
+
+#[cfg(feature = "num-traits")]
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    #[inline]
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        core::array::from_fn(|i| T::lerp(&a[i], &b[i], t))
+    }
+}
+
+#[cfg(all(feature = "num-traits", feature = "rgb"))]
+impl<T: Lerp> Lerp for rgb::RGB<T> {
+    #[inline]
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        rgb::RGB {
+            r: T::lerp(&a.r, &b.r, t),
+            g: T::lerp(&a.g, &b.g, t),
+            b: T::lerp(&a.b, &b.b, t),
+        }
+    }
+}
+
+#[cfg(all(feature = "num-traits", feature = "rgb"))]
+impl<T: Lerp> Lerp for rgb::RGBA<T> {
+    #[inline]
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        rgb::RGBA {
+            r: T::lerp(&a.r, &b.r, t),
+            g: T::lerp(&a.g, &b.g, t),
+            b: T::lerp(&a.b, &b.b, t),
+            a: T::lerp(&a.a, &b.a, t),
+        }
+    }
+}
+
+/// Blit the whole source surface to the `dest_size` rectangle at the start of the destination,
+/// resizing it with bilinear interpolation.
+///
+/// The value centers of both surfaces are aligned, and every destination value
+/// is interpolated between the four source values closest to its position.
+/// Positions past the centers of the border values use the border values (clamp-to-edge sampling),
+/// so the right and bottom edges are not blended with anything outside the source.
+/// Use `offset_surface` to move the rectangle.
+#[cfg(feature = "num-traits")]
+pub fn blit_bilinear<T: Lerp>(mut dest: impl SurfaceMut<T>, src: impl Surface<T>, dest_size: Size) {
+    let src_size = src.surface_size();
+    let dest_surface_size = dest.surface_size();
+
+    if src_size.x == 0 || src_size.y == 0 {
+        return;
+    }
+
+    for y in 0..dest_size.y.min(dest_surface_size.y) {
+        let (y0, y1, ty) = bilinear_coords(y, src_size.y, dest_size.y);
+
+        for x in 0..dest_size.x.min(dest_surface_size.x) {
+            let (x0, x1, tx) = bilinear_coords(x, src_size.x, dest_size.x);
+
+            let samples = (
+                src.surface_get(point(x0, y0)),
+                src.surface_get(point(x1, y0)),
+                src.surface_get(point(x0, y1)),
+                src.surface_get(point(x1, y1)),
+            );
+
+            if let ((Some(a), Some(b), Some(c), Some(d)), Some(dest)) =
+                (samples, dest.surface_get_mut(point(x, y)))
+            {
+                *dest = T::lerp(&T::lerp(a, b, tx), &T::lerp(c, d, tx), ty);
+            }
+        }
+    }
+}
+
+/// The two source coordinates around the center of the destination coordinate and the weight of the second one.
+#[cfg(feature = "num-traits")]
+#[inline]
+fn bilinear_coords(coord: u32, src_len: u32, dest_len: u32) -> (u32, u32, f32) {
+    let pos = (coord as f32 + 0.5) * src_len as f32 / dest_len as f32 - 0.5;
+    let pos = pos.max(0.0);
+
+    let low = (FloatCore::floor(pos) as u32).min(src_len - 1);
+    let high = (low + 1).min(src_len - 1);
+
+    (low, high, (pos - low as f32).min(1.0))
+}
+
+/// Numeric values that can be filtered by [`convolve_separable`] and [`blit_downscale_avg`].
+///
+/// Implemented for primitive integers (which are rounded and saturated) and floats.
+#[cfg(feature = "num-traits")]
+pub trait Convolve: Sized {
+    /// Convert the value to `f32` to be weighted by a kernel.
+    fn to_f32(&self) -> f32;
+
+    /// Convert a weighted sum back.
+    fn from_f32(value: f32) -> Self;
+}
+
+#[cfg(feature = "num-traits")]
+macro_rules! impl_convolve {
+    (int $($ty:ty),*) => {$(
+        impl Convolve for $ty {
+            #[inline]
+            fn to_f32(&self) -> f32 {
+                *self as f32
+            }
+
+            #[inline]
+            fn from_f32(value: f32) -> Self {
+                // `as` saturates
+                FloatCore::round(value) as $ty
+            }
+        }
+    )*};
+    (float $($ty:ty),*) => {$(
+        impl Convolve for $ty {
+            #[inline]
+            fn to_f32(&self) -> f32 {
+                *self as f32
+            }
+
+            #[inline]
+            fn from_f32(value: f32) -> Self {
+                value as $ty
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "num-traits")]
+impl_convolve!(int u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+#[cfg(feature = "num-traits")]
+impl_convolve!(float f32, f64);
+
+/// Filter the source surface with a horizontal and then a vertical 1D kernel, writing the result to the destination.
+///
+/// Every output value is `sum(kernel[i] * value[pos + i - kernel.len() / 2])` along each axis
+/// (so odd-length kernels are centered, and symmetric kernels don't need to be flipped).
+/// The source has to be wrapped in one of the edge handling views ([`EdgeClampSurface`](crate::EdgeClampSurface),
+/// [`WrapSurface`](crate::WrapSurface) or [`MirrorSurface`](crate::MirrorSurface)),
+/// which decides what's read past its borders.
+/// Only the part where both surfaces overlap is written, so they should have the same size.
+#[cfg(all(feature = "num-traits", feature = "alloc"))]
+pub fn convolve_separable<T: Convolve>(
+    mut dest: impl SurfaceMut<T>,
+    src: impl BorderSurface<T>,
+    kernel_x: &[f32],
+    kernel_y: &[f32],
+) {
+    let src_size = src.surface_size();
+    let dest_size = dest.surface_size();
+    let size = size(src_size.x.min(dest_size.x), src_size.y.min(dest_size.y));
+
+    let radius_x = (kernel_x.len() / 2) as i64;
+    let radius_y = (kernel_y.len() / 2) as i64;
+    let width = size.x as usize;
+
+    // the horizontal pass covers every row the vertical kernel reaches
+    let rows = size.y as usize + kernel_y.len().saturating_sub(1);
+    let mut horizontal = Vec::with_capacity(width * rows);
+
+    for row in 0..rows {
+        let y = row as i64 - radius_y;
+
+        for x in 0..size.x {
+            let sum = kernel_x
+                .iter()
+                .enumerate()
+                .filter_map(|(i, weight)| {
+                    let x = x as i64 + i as i64 - radius_x;
+
+                    src.border_get(signed_point(x as i32, y as i32))
+                        .map(|value| weight * value.to_f32())
+                })
+                .sum::<f32>();
+
+            horizontal.push(sum);
+        }
+    }
+
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let sum = kernel_y
+                .iter()
+                .enumerate()
+                .map(|(i, weight)| weight * horizontal[(y as usize + i) * width + x as usize])
+                .sum::<f32>();
+
+            if let Some(dest) = dest.surface_get_mut(point(x, y)) {
+                *dest = T::from_f32(sum);
+            }
+        }
+    }
+}
+
+/// Blit the source surface to the start of the destination, shrinking it by integer `factor`s
+/// by averaging each `factor.x * factor.y` block of source values into one destination value (a box filter).
+///
+/// Trailing source values that don't fill a whole block are ignored, and nothing is done if either factor is zero.
+/// Use `offset_surface` to move the destination rectangle.
+#[cfg(feature = "num-traits")]
+pub fn blit_downscale_avg<T: Convolve>(
+    mut dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    factor: Size,
+) {
+    if factor.x == 0 || factor.y == 0 {
+        return;
+    }
+
+    let src_size = src.surface_size();
+    let dest_size = dest.surface_size();
+    let count = factor.x as f32 * factor.y as f32;
+
+    for y in 0..(src_size.y / factor.y).min(dest_size.y) {
+        for x in 0..(src_size.x / factor.x).min(dest_size.x) {
+            let mut sum = 0.0;
+
+            for block_y in 0..factor.y {
+                for block_x in 0..factor.x {
+                    let pt = point(x * factor.x + block_x, y * factor.y + block_y);
+
+                    if let Some(value) = src.surface_get(pt) {
+                        sum += value.to_f32();
+                    }
+                }
+            }
+
+            if let Some(dest) = dest.surface_get_mut(point(x, y)) {
+                *dest = T::from_f32(sum / count);
+            }
+        }
+    }
+}
+
+/// Blit the source surface to the destination, rotated clockwise by `angle_radians` around its center,
+/// which is placed at `center` on the destination.
+///
+/// Every destination value inside the rotated source is inverse-rotated to the nearest source value
+/// (nearest-neighbor sampling), so this is lossy: edges get jagged and some values are dropped or repeated.
+/// Use [`Transform::Rotate90Cw`], [`Transform::Rotate180`] and [`Transform::Rotate90Ccw`] for exact right-angle rotations.
+#[cfg(feature = "libm")]
+pub fn blit_rotated<T: Clone>(
+    mut dest: impl SurfaceMut<T>,
+    src: impl Surface<T>,
+    center: Point,
+    angle_radians: f32,
+) {
+    let src_size = src.surface_size();
+    let dest_size = dest.surface_size();
+
+    if src_size.x == 0 || src_size.y == 0 {
+        return;
+    }
+
+    let (sin, cos) = libm::sincosf(angle_radians);
+    let src_center = ((src_size.x - 1) as f32 / 2.0, (src_size.y - 1) as f32 / 2.0);
+
+    // the rotated source fits in a circle around the center
+    let radius = (libm::ceilf(libm::hypotf(src_size.x as f32, src_size.y as f32) / 2.0)) as i64 + 1;
+    let range = |center: u32, len: u32| {
+        let center = i64::from(center);
+
+        (center - radius).clamp(0, len.into()) as u32
+            ..(center + radius + 1).clamp(0, len.into()) as u32
+    };
+
+    for y in range(center.y, dest_size.y) {
+        for x in range(center.x, dest_size.x) {
+            let dx = x as f32 - center.x as f32;
+            let dy = y as f32 - center.y as f32;
+
+            let sx = libm::roundf(dx * cos + dy * sin + src_center.0);
+            let sy = libm::roundf(dy * cos - dx * sin + src_center.1);
+
+            if sx < 0.0 || sy < 0.0 || sx >= src_size.x as f32 || sy >= src_size.y as f32 {
+                continue;
+            }
+
+            if let (Some(dest), Some(src)) = (
+                dest.surface_get_mut(point(x, y)),
+                src.surface_get(point(sx as u32, sy as u32)),
+            ) {
+                dest.clone_from(src);
+            }
+        }
+    }
+}
+
+/// Blit part of one surface to another, blending the source over the destination using its alpha.
+///
+/// Uses straight-alpha `over` compositing: `out = src.a * src + (1 - src.a) * dst` for the color channels,
+/// and `out.a = src.a + (1 - src.a) * dst.a` for the alpha channel.
+/// Fully opaque source pixels are copied exactly, fully transparent ones leave the destination untouched.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[cfg(feature = "rgb")]
+#[inline]
+pub fn blit_blend(
+    dest: impl SurfaceMut<RGBA8>,
+    src: impl Surface<RGBA8>,
+    transforms: &[Transform],
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        *dest = blend_over(*dest, *src);
+    });
+}
+
+/// Blit part of one surface to another, multiplying the source by `tint` and blending it over the destination.
+///
+/// Each source channel (alpha included) is multiplied by the matching tint channel divided by 255,
+/// so a white opaque tint changes nothing and a lower tint alpha fades the source out.
+/// The tinted source is then blended like in [`blit_blend`].
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[cfg(feature = "rgb")]
+#[inline]
+pub fn blit_tinted(
+    dest: impl SurfaceMut<RGBA8>,
+    src: impl Surface<RGBA8>,
+    transforms: &[Transform],
+    tint: RGBA8,
+) {
+    let multiply = |value: u8, tint: u8| ((value as u32 * tint as u32 + 127) / 255) as u8;
+
+    blit_with(dest, src, transforms, |dest, src, _| {
+        let tinted = RGBA8 {
+            r: multiply(src.r, tint.r),
+            g: multiply(src.g, tint.g),
+            b: multiply(src.b, tint.b),
+            a: multiply(src.a, tint.a),
+        };
+
+        *dest = blend_over(*dest, tinted);
+    });
+}
+
+/// Luminance weights used by [`blit_grayscale`] (ITU-R BT.601).
+#[cfg(feature = "rgb")]
+pub const LUMA_WEIGHTS: [f32; 3] = [0.299, 0.587, 0.114];
+
+/// Blit part of one surface to another, converting it to grayscale.
+///
+/// The luminance is computed with [`LUMA_WEIGHTS`] and written to all three color channels,
+/// the source alpha is kept as is.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[cfg(feature = "rgb")]
+#[inline]
+pub fn blit_grayscale(
+    dest: impl SurfaceMut<RGBA8>,
+    src: impl Surface<RGBA8>,
+    transforms: &[Transform],
+) {
+    blit_grayscale_weighted(dest, src, transforms, LUMA_WEIGHTS);
+}
+
+/// Blit part of one surface to another, converting it to grayscale with custom channel `weights`.
+///
+/// The luminance is `r * weights[0] + g * weights[1] + b * weights[2]`, rounded and clamped to `0..=255`.
+/// It's written to all three color channels, the source alpha is kept as is.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[cfg(feature = "rgb")]
+#[inline]
+pub fn blit_grayscale_weighted(
+    dest: impl SurfaceMut<RGBA8>,
+    src: impl Surface<RGBA8>,
+    transforms: &[Transform],
+    weights: [f32; 3],
+) {
+    blit_with(dest, src, transforms, |dest, src, _| {
+        let luma =
+            src.r as f32 * weights[0] + src.g as f32 * weights[1] + src.b as f32 * weights[2];
+        // `as` saturates, so out-of-range weights can't wrap around
+        let luma = (luma + 0.5) as u8;
+
+        *dest = RGBA8 {
+            r: luma,
+            g: luma,
+            b: luma,
+            a: src.a,
+        };
+    });
+}
+
+/// Multiply the color channels of every value of the surface by its alpha, in place.
+///
+/// Converts straight alpha to premultiplied alpha, which filtering (like `blit_bilinear`) and blending
+/// need to handle transparent values correctly. Fully transparent values become transparent black.
+/// Use [`unpremultiply`] to convert back.
+#[cfg(feature = "rgb")]
+pub fn premultiply(mut surface: impl SurfaceMut<RGBA8>) {
+    map_in_place(&mut surface, |value| {
+        let multiply = |c: u8| ((c as u32 * value.a as u32 + 127) / 255) as u8;
+
+        RGBA8 {
+            r: multiply(value.r),
+            g: multiply(value.g),
+            b: multiply(value.b),
+            a: value.a,
+        }
+    });
+}
+
+/// Divide the color channels of every value of the surface by its alpha, in place.
+///
+/// Converts premultiplied alpha back to straight alpha, undoing [`premultiply`] up to rounding:
+/// the lower the alpha, the less precise the colors are. Fully transparent values are left as they are,
+/// and the color channels are clamped to 255 if they're bigger than the alpha.
+#[cfg(feature = "rgb")]
+pub fn unpremultiply(mut surface: impl SurfaceMut<RGBA8>) {
+    map_in_place(&mut surface, |value| {
+        if value.a == 0 {
+            return value;
+        }
+
+        let alpha = value.a as u32;
+        let divide = |c: u8| ((c as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+
+        RGBA8 {
+            r: divide(value.r),
+            g: divide(value.g),
+            b: divide(value.b),
+            a: value.a,
+        }
+    });
+}
+
+/// Replace every value of the surface with the result of calling `func` with it.
+#[cfg(feature = "rgb")]
+#[inline]
+fn map_in_place<T: Copy>(surface: &mut impl SurfaceMut<T>, mut func: impl FnMut(T) -> T) {
+    let size = surface.surface_size();
+
+    for y in 0..size.y {
+        for x in 0..size.x {
+            if let Some(value) = surface.surface_get_mut(point(x, y)) {
+                *value = (func)(*value);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rgb")]
+#[inline]
+fn blend_over(dest: RGBA8, src: RGBA8) -> RGBA8 {
+    match src.a {
+        u8::MAX => src,
+        0 => dest,
+        alpha => {
+            let alpha = alpha as u32;
+            let inv_alpha = u8::MAX as u32 - alpha;
+            let mix = |s: u8, d: u8| ((s as u32 * alpha + d as u32 * inv_alpha + 127) / 255) as u8;
+
+            RGBA8 {
+                r: mix(src.r, dest.r),
+                g: mix(src.g, dest.g),
+                b: mix(src.b, dest.b),
+                a: (alpha + (dest.a as u32 * inv_alpha + 127) / 255) as u8,
+            }
+        }
+    }
+}
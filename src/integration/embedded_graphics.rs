@@ -0,0 +1,47 @@
+use crate::{point, GenericSurface, Surface, SurfaceMut};
+use core::convert::Infallible;
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point as EgPoint, Size as EgSize},
+    pixelcolor::PixelColor,
+    primitives::Rectangle,
+    Pixel,
+};
+
+impl<Slice, Item> Dimensions for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]>,
+{
+    #[inline]
+    fn bounding_box(&self) -> Rectangle {
+        let size = self.surface_size();
+
+        Rectangle::new(EgPoint::zero(), EgSize::new(size.x, size.y))
+    }
+}
+
+/// Pixels outside of the surface are ignored.
+impl<Slice, Item> DrawTarget for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]> + AsMut<[Item]>,
+    Item: PixelColor,
+{
+    type Color = Item;
+    type Error = Infallible;
+
+    #[inline]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(pt, color) in pixels {
+            if let (Ok(x), Ok(y)) = (u32::try_from(pt.x), u32::try_from(pt.y)) {
+                if let Some(value) = self.surface_get_mut(point(x, y)) {
+                    *value = color;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
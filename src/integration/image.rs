@@ -1,8 +1,9 @@
-use crate::{size, Point, Size, Surface, SurfaceMut};
+use crate::{blit_with, size, Point, Size, Surface, SurfaceMut, Transform};
+use alloc::borrow::Cow;
 use core::ops::{Deref, DerefMut};
-use image::Pixel;
+use image::{Luma, LumaA, Pixel, Rgb, Rgba, RgbaImage};
 
-pub use image::ImageBuffer;
+pub use image::{DynamicImage, ImageBuffer};
 
 impl<Pix, Container> Surface<Pix> for ImageBuffer<Pix, Container>
 where
@@ -30,3 +31,100 @@ where
         self.get_pixel_mut_checked(pt.x, pt.y)
     }
 }
+
+/// Returns a [`DynamicImage`] as an `Rgba8` surface.
+///
+/// `DynamicImage` can't implement [`Surface`] itself, since the surface has to return references
+/// and the image may store its pixels in another format. If it does, the image is converted,
+/// which allocates a copy and is lossy for 16-bit and floating point formats.
+#[inline]
+pub fn to_rgba8_surface(image: &DynamicImage) -> Cow<'_, RgbaImage> {
+    match image.as_rgba8() {
+        Some(buffer) => Cow::Borrowed(buffer),
+        None => Cow::Owned(image.to_rgba8()),
+    }
+}
+
+/// Returns a [`DynamicImage`] as a mutable `Rgba8` surface.
+///
+/// If the image stores its pixels in another format, it's converted to `Rgba8` in place first,
+/// which allocates a new buffer and is lossy for 16-bit and floating point formats.
+/// The image stays `Rgba8` afterwards, so later calls are free.
+#[inline]
+pub fn as_rgba8_surface_mut(image: &mut DynamicImage) -> &mut RgbaImage {
+    if image.as_rgba8().is_none() {
+        *image = DynamicImage::ImageRgba8(image.to_rgba8());
+    }
+
+    match image {
+        DynamicImage::ImageRgba8(buffer) => buffer,
+        _ => unreachable!(),
+    }
+}
+
+/// Pixel types that [`blit_image_convert`] can convert to.
+pub trait FromRgba: Pixel {
+    /// Convert an RGBA pixel to this pixel type, dropping the channels it doesn't have.
+    fn from_rgba(rgba: Rgba<Self::Subpixel>) -> Self;
+}
+
+macro_rules! impl_from_rgba {
+    ($($subpixel:ty),*) => {$(
+        impl FromRgba for Rgba<$subpixel> {
+            #[inline]
+            fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                rgba
+            }
+        }
+
+        impl FromRgba for Rgb<$subpixel> {
+            #[inline]
+            fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                rgba.to_rgb()
+            }
+        }
+
+        impl FromRgba for LumaA<$subpixel> {
+            #[inline]
+            fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                rgba.to_luma_alpha()
+            }
+        }
+
+        impl FromRgba for Luma<$subpixel> {
+            #[inline]
+            fn from_rgba(rgba: Rgba<$subpixel>) -> Self {
+                rgba.to_luma()
+            }
+        }
+    )*};
+}
+
+// the subpixel types used by `image::DynamicImage`
+impl_from_rgba!(u8, u16, f32);
+
+/// Blit part of one image surface to another with a different pixel type (with the same subpixel type),
+/// compositing the source over the destination.
+///
+/// The source alpha is blended onto the destination before converting,
+/// so e.g. an RGBA sprite is flattened correctly onto an RGB background.
+///
+/// You can use `sub_surface` or `offset_surface` functions to limit the copied area.
+/// The transforms are done in order.
+#[inline]
+pub fn blit_image_convert<D, S>(
+    dest: impl SurfaceMut<D>,
+    src: impl Surface<S>,
+    transforms: &[Transform],
+) where
+    D: FromRgba,
+    S: Pixel<Subpixel = D::Subpixel>,
+    Rgba<D::Subpixel>: Pixel<Subpixel = D::Subpixel>,
+{
+    blit_with(dest, src, transforms, |dest, src, _| {
+        let mut rgba = dest.to_rgba();
+        rgba.blend(&src.to_rgba());
+
+        *dest = D::from_rgba(rgba);
+    });
+}
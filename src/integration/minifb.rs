@@ -0,0 +1,41 @@
+use crate::{GenericSurface, Surface};
+
+pub use minifb::Window;
+
+/// Packs the color channels into the `0x00RRGGBB` format used by [`Window::update_with_buffer`].
+#[inline]
+pub const fn minifb_color(r: u8, g: u8, b: u8) -> u32 {
+    ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+}
+
+/// Construct a surface over a minifb framebuffer, with values in `0x00RRGGBB` format (see [`minifb_color`]).
+///
+/// Takes the size as `usize`s like [`Window::update_with_buffer`] does.
+/// Returns `None` if `buffer.len() != width * height` or if the size doesn't fit in `u32`.
+#[inline]
+pub fn minifb_surface(
+    buffer: &mut [u32],
+    width: usize,
+    height: usize,
+) -> Option<GenericSurface<&mut [u32], u32>> {
+    let size = crate::size(u32::try_from(width).ok()?, u32::try_from(height).ok()?);
+
+    GenericSurface::new(buffer, size)
+}
+
+/// Show the surface in `window` with [`Window::update_with_buffer`].
+///
+/// Padded surfaces (see [`GenericSurface::with_stride`]) are copied to a compact buffer first.
+#[inline]
+pub fn present<S: AsRef<[u32]>>(
+    window: &mut Window,
+    surface: &GenericSurface<S, u32>,
+) -> minifb::Result<()> {
+    let size = surface.surface_size();
+
+    if surface.stride() == size.x {
+        window.update_with_buffer(surface, size.x as usize, size.y as usize)
+    } else {
+        window.update_with_buffer(&surface.to_owned(), size.x as usize, size.y as usize)
+    }
+}
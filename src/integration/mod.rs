@@ -3,7 +3,23 @@ mod pixels;
 #[cfg(feature = "pixels-integration")]
 pub use pixels::*;
 
+#[cfg(feature = "softbuffer-integration")]
+mod softbuffer;
+#[cfg(feature = "softbuffer-integration")]
+pub use self::softbuffer::*;
+
+#[cfg(feature = "minifb-integration")]
+mod minifb;
+#[cfg(feature = "minifb-integration")]
+pub use self::minifb::*;
+
 #[cfg(feature = "image-integration")]
 mod image;
 #[cfg(feature = "image-integration")]
 pub use image::*;
+
+#[cfg(feature = "embedded-graphics-integration")]
+mod embedded_graphics;
+
+#[cfg(feature = "ndarray-integration")]
+mod ndarray;
@@ -0,0 +1,48 @@
+use crate::{size, Point, Size, Surface, SurfaceMut};
+use ndarray::{ArrayBase, Data, DataMut, Ix2};
+
+/// The first axis is y (the rows) and the second one is x, so `surface_get(point(x, y))` is `array[[y, x]]`.
+impl<S, T> Surface<T> for ArrayBase<S, Ix2>
+where
+    S: Data<Elem = T>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        let (rows, columns) = self.dim();
+
+        size(columns as u32, rows as u32)
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        self.get([pt.y as usize, pt.x as usize])
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[T]> {
+        if (y as usize) < self.nrows() {
+            self.row(y as usize).to_slice()
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, T> SurfaceMut<T> for ArrayBase<S, Ix2>
+where
+    S: DataMut<Elem = T>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        self.get_mut([pt.y as usize, pt.x as usize])
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [T]> {
+        if (y as usize) < self.nrows() {
+            self.row_mut(y as usize).into_slice()
+        } else {
+            None
+        }
+    }
+}
@@ -1,8 +1,18 @@
-use crate::{size, Point, Size, Surface, SurfaceMut};
+use crate::{blit, blit_scaled, size, Point, Size, Surface, SurfaceMut};
+use core::borrow::{Borrow, BorrowMut};
 use rgb::AsPixels;
 
 pub use pixels::Pixels;
-pub use rgb::RGBA8;
+pub use rgb::{alt::BGRA8, RGBA8};
+
+/// Index of `pt` in the frame, if it's inside it.
+#[inline]
+fn frame_index(pixels: &Pixels, pt: Point) -> Option<usize> {
+    let texture = pixels.texture();
+
+    (pt.x < texture.width() && pt.y < texture.height())
+        .then(|| pt.y as usize * texture.width() as usize + pt.x as usize)
+}
 
 impl Surface<RGBA8> for Pixels {
     #[inline]
@@ -12,19 +22,149 @@ impl Surface<RGBA8> for Pixels {
 
     #[inline]
     fn surface_get(&self, pt: Point) -> Option<&RGBA8> {
-        self.frame()
-            .as_pixels()
-            .get((pt.y * self.texture().width() + pt.x) as usize)
+        self.frame().as_pixels().get(frame_index(self, pt)?)
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[RGBA8]> {
+        let width = self.texture().width().max(1) as usize;
+
+        self.frame().as_pixels().chunks_exact(width).nth(y as usize)
     }
 }
 
 impl SurfaceMut<RGBA8> for Pixels {
     #[inline]
     fn surface_get_mut(&mut self, pt: Point) -> Option<&mut RGBA8> {
-        let width = self.texture().width();
+        let index = frame_index(self, pt)?;
+
+        self.frame_mut().as_pixels_mut().get_mut(index)
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [RGBA8]> {
+        let width = self.texture().width().max(1) as usize;
 
         self.frame_mut()
             .as_pixels_mut()
-            .get_mut((pt.y * width + pt.x) as usize)
+            .chunks_exact_mut(width)
+            .nth(y as usize)
+    }
+}
+
+/// Copy `src` to the start of the `pixels` frame, clipping whatever doesn't fit.
+///
+/// Same as `blit(pixels, src, &[])`, the rows are copied at once if `src` provides them.
+/// Use [`resize_surface`] if the sizes may not match and the whole frame should be covered.
+#[inline]
+pub fn copy_to_pixels(src: &impl Surface<RGBA8>, pixels: &mut Pixels) {
+    blit(pixels, src, &[]);
+}
+
+/// Copy `src` to the whole `pixels` frame, scaling it (with nearest-neighbor sampling) if the sizes don't match.
+///
+/// Does the same as [`copy_to_pixels`] if they do.
+#[inline]
+pub fn resize_surface(src: &impl Surface<RGBA8>, pixels: &mut Pixels) {
+    let frame_size = Surface::<RGBA8>::surface_size(pixels);
+
+    if src.surface_size() == frame_size {
+        copy_to_pixels(src, pixels);
+    } else {
+        blit_scaled(pixels, src, frame_size);
+    }
+}
+
+/// [`Pixels`] frame with the channels in BGRA order, for backends that expect it.
+///
+/// Blit `BGRA8` values to it, e.g. by converting `RGBA8` surfaces with [`blit_convert`](crate::blit_convert).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BgraPixels<P> {
+    pixels: P,
+}
+
+impl<P> BgraPixels<P> {
+    /// Construct a new BGRA view of `pixels` (which can be `Pixels`, `&Pixels` or `&mut Pixels`).
+    #[inline]
+    pub fn new(pixels: P) -> Self {
+        Self { pixels }
+    }
+
+    /// Returns a reference to the inner pixels.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pixels
+    }
+
+    /// Returns a mutable reference to the inner pixels.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pixels
+    }
+
+    /// Returns the inner pixels.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pixels
+    }
+}
+
+/// Create a [`BgraPixels`] view of `pixels`.
+#[inline]
+pub fn bgra<P: Borrow<Pixels>>(pixels: P) -> BgraPixels<P> {
+    BgraPixels::new(pixels)
+}
+
+impl<P> Surface<BGRA8> for BgraPixels<P>
+where
+    P: Borrow<Pixels>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        Surface::<RGBA8>::surface_size(self.pixels.borrow())
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&BGRA8> {
+        let pixels = self.pixels.borrow();
+
+        pixels.frame().as_pixels().get(frame_index(pixels, pt)?)
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[BGRA8]> {
+        let pixels = self.pixels.borrow();
+        let width = pixels.texture().width().max(1) as usize;
+
+        pixels
+            .frame()
+            .as_pixels()
+            .chunks_exact(width)
+            .nth(y as usize)
+    }
+}
+
+impl<P> SurfaceMut<BGRA8> for BgraPixels<P>
+where
+    P: BorrowMut<Pixels>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut BGRA8> {
+        let pixels = self.pixels.borrow_mut();
+        let index = frame_index(pixels, pt)?;
+
+        pixels.frame_mut().as_pixels_mut().get_mut(index)
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [BGRA8]> {
+        let pixels = self.pixels.borrow_mut();
+        let width = pixels.texture().width().max(1) as usize;
+
+        pixels
+            .frame_mut()
+            .as_pixels_mut()
+            .chunks_exact_mut(width)
+            .nth(y as usize)
     }
 }
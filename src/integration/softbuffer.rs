@@ -0,0 +1,64 @@
+use crate::{size, Point, Size, Surface, SurfaceMut};
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+
+pub use softbuffer::Buffer;
+
+/// Packs the color channels into the `0x00RRGGBB` format used by [`Buffer`].
+#[inline]
+pub const fn softbuffer_color(r: u8, g: u8, b: u8) -> u32 {
+    ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+}
+
+/// The values are in `0x00RRGGBB` format (see [`softbuffer_color`]).
+impl<D, W> Surface<u32> for Buffer<'_, D, W>
+where
+    D: HasDisplayHandle,
+    W: HasWindowHandle,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        size(self.width().get(), self.height().get())
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&u32> {
+        let size = self.surface_size();
+
+        if pt.x < size.x && pt.y < size.y {
+            self[..].get(pt.y as usize * size.x as usize + pt.x as usize)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[u32]> {
+        let width = self.width().get() as usize;
+
+        self.chunks_exact(width).nth(y as usize)
+    }
+}
+
+impl<D, W> SurfaceMut<u32> for Buffer<'_, D, W>
+where
+    D: HasDisplayHandle,
+    W: HasWindowHandle,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut u32> {
+        let size = self.surface_size();
+
+        if pt.x < size.x && pt.y < size.y {
+            self[..].get_mut(pt.y as usize * size.x as usize + pt.x as usize)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [u32]> {
+        let width = self.width().get() as usize;
+
+        self.chunks_exact_mut(width).nth(y as usize)
+    }
+}
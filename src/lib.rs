@@ -2,9 +2,16 @@
 #![warn(missing_docs)]
 #![doc = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md"))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod draw;
 mod funcs;
 mod types;
 
+pub use draw::*;
 pub use funcs::*;
 pub use types::*;
 
@@ -1,16 +1,23 @@
 extern crate alloc;
 
 use self::predefined::Predefined;
-use crate::{blit, point, size, GenericSurface, Surface, Transform};
-use alloc::format;
+use crate::{
+    blit, blit_bitand, blit_bitor, blit_bitxor, blit_exact, blit_masked_by, blit_scaled,
+    blit_with_count, blit_with_positions, point, size, try_blit_with, ArraySurface, CheckerSurface,
+    ConcatH, ConcatV, EdgeClampSurface, FnSurface, GenericSurface, MirrorSurface, PadSurface,
+    SingleValueSurface, Surface, SurfaceMut, Transform,
+};
+use alloc::vec::Vec;
+use core::ops::Bound;
 use proptest::{
-    prelude::prop,
-    prop_assert_eq,
+    prelude::{prop, Strategy},
+    prop_assert, prop_assert_eq,
     test_runner::{Config, TestRunner},
 };
 
 mod predefined {
-    use crate::{size, GenericSurface, Transform};
+    use super::Vec;
+    use crate::{size, GenericSurface, Size, Transform};
 
     #[rustfmt::skip]
     const TOP_LEFT: [u8; 9] = [
@@ -19,16 +26,6 @@ mod predefined {
         3, 4, 5,
     ];
 
-    #[rustfmt::skip]
-    const TOP_LEFT_2X: [u8; 36] = [
-        1, 1, 2, 2, 3, 3,
-        1, 1, 2, 2, 3, 3,
-        2, 2, 3, 3, 4, 4,
-        2, 2, 3, 3, 4, 4,
-        3, 3, 4, 4, 5, 5,
-        3, 3, 4, 4, 5, 5,
-    ];
-
     #[rustfmt::skip]
     const TOP_RIGHT: [u8; 9] = [
         3, 2, 1,
@@ -36,16 +33,6 @@ mod predefined {
         5, 4, 3,
     ];
 
-    #[rustfmt::skip]
-    const TOP_RIGHT_2X: [u8; 36] = [
-        3, 3, 2, 2, 1, 1,
-        3, 3, 2, 2, 1, 1,
-        4, 4, 3, 3, 2, 2,
-        4, 4, 3, 3, 2, 2,
-        5, 5, 4, 4, 3, 3,
-        5, 5, 4, 4, 3, 3,
-    ];
-
     #[rustfmt::skip]
     const BOTTOM_LEFT: [u8; 9] = [
         3, 4, 5,
@@ -53,16 +40,6 @@ mod predefined {
         1, 2, 3,
     ];
 
-    #[rustfmt::skip]
-    const BOTTOM_LEFT_2X: [u8; 36] = [
-        3, 3, 4, 4, 5, 5,
-        3, 3, 4, 4, 5, 5,
-        2, 2, 3, 3, 4, 4,
-        2, 2, 3, 3, 4, 4,
-        1, 1, 2, 2, 3, 3,
-        1, 1, 2, 2, 3, 3,
-    ];
-
     #[rustfmt::skip]
     const BOTTOM_RIGHT: [u8; 9] = [
         5, 4, 3,
@@ -70,27 +47,18 @@ mod predefined {
         3, 2, 1,
     ];
 
-    #[rustfmt::skip]
-    const BOTTOM_RIGHT_2X: [u8; 36] = [
-        5, 5, 4, 4, 3, 3,
-        5, 5, 4, 4, 3, 3,
-        4, 4, 3, 3, 2, 2,
-        4, 4, 3, 3, 2, 2,
-        3, 3, 2, 2, 1, 1,
-        3, 3, 2, 2, 1, 1,
-    ];
-
+    /// One of the 3x3 corner surfaces, upscaled by the contained factor.
     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub enum Predefined {
-        TopLeft(bool),
-        TopRight(bool),
-        BottomLeft(bool),
-        BottomRight(bool),
+        TopLeft(Size),
+        TopRight(Size),
+        BottomLeft(Size),
+        BottomRight(Size),
     }
 
     impl Predefined {
         #[inline]
-        pub fn is_scaled(self) -> bool {
+        pub fn scale(self) -> Size {
             match self {
                 Predefined::TopLeft(s) => s,
                 Predefined::TopRight(s) => s,
@@ -99,70 +67,104 @@ mod predefined {
             }
         }
 
-        pub fn surface(self) -> GenericSurface<&'static [u8], u8> {
+        /// Checks whether the scale can be divided by the factors without a remainder.
+        // `u32::is_multiple_of` needs a newer Rust than the crate otherwise does
+        #[allow(clippy::manual_is_multiple_of)]
+        #[inline]
+        pub fn divisible_by(self, x: u32, y: u32) -> bool {
+            let scale = self.scale();
+
+            scale.x % x == 0 && scale.y % y == 0
+        }
+
+        #[inline]
+        fn with_scale(self, scale: Size) -> Self {
+            use Predefined::*;
+
+            match self {
+                TopLeft(_) => TopLeft(scale),
+                TopRight(_) => TopRight(scale),
+                BottomLeft(_) => BottomLeft(scale),
+                BottomRight(_) => BottomRight(scale),
+            }
+        }
+
+        pub fn surface(self) -> GenericSurface<Vec<u8>, u8> {
             use Predefined::*;
 
-            let (slice, size) = match self {
-                TopLeft(false) => (&TOP_LEFT[..], size(3, 3)),
-                TopLeft(true) => (&TOP_LEFT_2X[..], size(6, 6)),
-                TopRight(false) => (&TOP_RIGHT[..], size(3, 3)),
-                TopRight(true) => (&TOP_RIGHT_2X[..], size(6, 6)),
-                BottomLeft(false) => (&BOTTOM_LEFT[..], size(3, 3)),
-                BottomLeft(true) => (&BOTTOM_LEFT_2X[..], size(6, 6)),
-                BottomRight(false) => (&BOTTOM_RIGHT[..], size(3, 3)),
-                BottomRight(true) => (&BOTTOM_RIGHT_2X[..], size(6, 6)),
+            let base = match self {
+                TopLeft(_) => &TOP_LEFT,
+                TopRight(_) => &TOP_RIGHT,
+                BottomLeft(_) => &BOTTOM_LEFT,
+                BottomRight(_) => &BOTTOM_RIGHT,
             };
+            let scale = self.scale();
+            let size = size(3 * scale.x, 3 * scale.y);
 
-            GenericSurface::new(slice, size).unwrap()
+            let values = (0..size.y)
+                .flat_map(|y| (0..size.x).map(move |x| (x, y)))
+                .map(|(x, y)| base[(y / scale.y * 3 + x / scale.x) as usize])
+                .collect();
+
+            GenericSurface::new(values, size).unwrap()
         }
 
         pub fn transform(self, tr: Transform) -> Self {
             use Predefined::*;
             use Transform::*;
 
+            let scale = self.scale();
+
             match tr {
-                UpScale { x: 1, y: 1 } => self,
-                UpScale { x: 2, y: 2 } if self == TopLeft(false) => TopLeft(true),
-                UpScale { x: 2, y: 2 } if self == TopRight(false) => TopRight(true),
-                UpScale { x: 2, y: 2 } if self == BottomLeft(false) => BottomRight(true),
-                UpScale { x: 2, y: 2 } if self == BottomRight(false) => BottomRight(true),
-                UpScale { .. } => panic!("not supported for auto tests"),
-
-                FlipHorizontal if matches!(self, TopLeft(_)) => TopRight(self.is_scaled()),
-                FlipHorizontal if matches!(self, TopRight(_)) => TopLeft(self.is_scaled()),
-                FlipHorizontal if matches!(self, BottomLeft(_)) => BottomRight(self.is_scaled()),
-                FlipHorizontal if matches!(self, BottomRight(_)) => BottomLeft(self.is_scaled()),
-                FlipHorizontal => unreachable!(),
-
-                FlipVertical if matches!(self, TopLeft(_)) => BottomLeft(self.is_scaled()),
-                FlipVertical if matches!(self, TopRight(_)) => BottomRight(self.is_scaled()),
-                FlipVertical if matches!(self, BottomLeft(_)) => TopLeft(self.is_scaled()),
-                FlipVertical if matches!(self, BottomRight(_)) => TopRight(self.is_scaled()),
-                FlipVertical => unreachable!(),
-
-                FlipBoth if matches!(self, TopLeft(_)) => BottomRight(self.is_scaled()),
-                FlipBoth if matches!(self, TopRight(_)) => BottomLeft(self.is_scaled()),
-                FlipBoth if matches!(self, BottomLeft(_)) => TopRight(self.is_scaled()),
-                FlipBoth if matches!(self, BottomRight(_)) => TopLeft(self.is_scaled()),
-                FlipBoth => unreachable!(),
-
-                Rotate90Cw if matches!(self, TopLeft(_)) => TopRight(self.is_scaled()),
-                Rotate90Cw if matches!(self, TopRight(_)) => BottomRight(self.is_scaled()),
-                Rotate90Cw if matches!(self, BottomRight(_)) => BottomLeft(self.is_scaled()),
-                Rotate90Cw if matches!(self, BottomLeft(_)) => TopLeft(self.is_scaled()),
-                Rotate90Cw => unreachable!(),
-
-                Rotate90Ccw if matches!(self, TopLeft(_)) => BottomLeft(self.is_scaled()),
-                Rotate90Ccw if matches!(self, BottomLeft(_)) => BottomRight(self.is_scaled()),
-                Rotate90Ccw if matches!(self, BottomRight(_)) => TopRight(self.is_scaled()),
-                Rotate90Ccw if matches!(self, TopRight(_)) => TopLeft(self.is_scaled()),
-                Rotate90Ccw => unreachable!(),
-
-                Rotate180 if matches!(self, TopLeft(_)) => BottomRight(self.is_scaled()),
-                Rotate180 if matches!(self, TopRight(_)) => BottomLeft(self.is_scaled()),
-                Rotate180 if matches!(self, BottomLeft(_)) => TopRight(self.is_scaled()),
-                Rotate180 if matches!(self, BottomRight(_)) => TopLeft(self.is_scaled()),
-                Rotate180 => unreachable!(),
+                UpScale { x, y } => self.with_scale(size(scale.x * x, scale.y * y)),
+
+                DownScale { x, y } if self.divisible_by(x, y) => {
+                    self.with_scale(size(scale.x / x, scale.y / y))
+                }
+                DownScale { .. } => panic!("not supported for auto tests"),
+
+                FlipHorizontal => match self {
+                    TopLeft(s) => TopRight(s),
+                    TopRight(s) => TopLeft(s),
+                    BottomLeft(s) => BottomRight(s),
+                    BottomRight(s) => BottomLeft(s),
+                },
+
+                FlipVertical => match self {
+                    TopLeft(s) => BottomLeft(s),
+                    TopRight(s) => BottomRight(s),
+                    BottomLeft(s) => TopLeft(s),
+                    BottomRight(s) => TopRight(s),
+                },
+
+                FlipBoth | Rotate180 => match self {
+                    TopLeft(s) => BottomRight(s),
+                    TopRight(s) => BottomLeft(s),
+                    BottomLeft(s) => TopRight(s),
+                    BottomRight(s) => TopLeft(s),
+                },
+
+                // quarter turns and transposition swap the axes, scale included
+                Rotate90Cw => match self {
+                    TopLeft(s) => TopRight(size(s.y, s.x)),
+                    TopRight(s) => BottomRight(size(s.y, s.x)),
+                    BottomRight(s) => BottomLeft(size(s.y, s.x)),
+                    BottomLeft(s) => TopLeft(size(s.y, s.x)),
+                },
+
+                Rotate90Ccw => match self {
+                    TopLeft(s) => BottomLeft(size(s.y, s.x)),
+                    BottomLeft(s) => BottomRight(size(s.y, s.x)),
+                    BottomRight(s) => TopRight(size(s.y, s.x)),
+                    TopRight(s) => TopLeft(size(s.y, s.x)),
+                },
+
+                Transpose => match self {
+                    TopLeft(s) => TopLeft(size(s.y, s.x)),
+                    TopRight(s) => BottomLeft(size(s.y, s.x)),
+                    BottomLeft(s) => TopRight(size(s.y, s.x)),
+                    BottomRight(s) => BottomRight(size(s.y, s.x)),
+                },
             }
         }
     }
@@ -171,46 +173,79 @@ mod predefined {
 #[test]
 fn transforms() {
     let transforms = prop::collection::vec(
+        prop::strategy::Union::new([
+            prop::sample::select(
+                &[
+                    Transform::FlipHorizontal,
+                    Transform::FlipVertical,
+                    Transform::FlipBoth,
+                    Transform::Rotate90Cw,
+                    Transform::Rotate90Ccw,
+                    Transform::Rotate180,
+                    Transform::Transpose,
+                ][..],
+            )
+            .boxed(),
+            (1..=3_u32, 1..=3_u32)
+                .prop_map(|(x, y)| Transform::UpScale { x, y })
+                .boxed(),
+            (1..=3_u32, 1..=3_u32)
+                .prop_map(|(x, y)| Transform::DownScale { x, y })
+                .boxed(),
+        ]),
+        0..=12,
+    );
+
+    let sources = (
         prop::sample::select(
             &[
-                Transform::FlipHorizontal,
-                Transform::FlipVertical,
-                Transform::FlipBoth,
-                Transform::Rotate90Cw,
-                Transform::Rotate90Ccw,
-                Transform::Rotate180,
+                Predefined::TopLeft as fn(_) -> _,
+                Predefined::TopRight,
+                Predefined::BottomLeft,
+                Predefined::BottomRight,
             ][..],
         ),
-        0..=12,
-    );
+        1..=3_u32,
+        1..=3_u32,
+    )
+        .prop_map(|(corner, x, y)| corner(size(x, y)));
 
-    let sources = prop::sample::select(
-        &[
-            Predefined::TopLeft(false),
-            Predefined::TopRight(false),
-            Predefined::BottomLeft(false),
-            Predefined::BottomRight(false),
-        ][..],
-    );
+    // only keep the scaling that the predefined surfaces support, without letting them grow too big
+    let cases = (sources, transforms).prop_map(|(src, transforms)| {
+        let mut state = src;
+
+        let transforms = transforms
+            .into_iter()
+            .filter(|&tr| {
+                let scale = state.scale();
+                let keep = match tr {
+                    Transform::UpScale { x, y } => scale.x * x <= 6 && scale.y * y <= 6,
+                    Transform::DownScale { x, y } => state.divisible_by(x, y),
+                    _ => true,
+                };
+
+                if keep {
+                    state = state.transform(tr);
+                }
+
+                keep
+            })
+            .collect::<Vec<_>>();
+
+        (src, transforms)
+    });
 
     let mut runner = TestRunner::new(Config::with_cases(10_000));
 
-    let result = runner.run(&(sources, transforms), |(src, transforms)| {
+    let result = runner.run(&cases, |(src, transforms)| {
         let desired = transforms
             .iter()
             .copied()
             .fold(src, |src, tr| src.transform(tr))
             .surface();
 
-        let mut dest_array = [0_u8; 9];
-        let mut dest_array_scaled = [0_u8; 36];
-        let mut dest;
-
-        if desired.surface_size() == size(3, 3) {
-            dest = GenericSurface::new(&mut dest_array[..], size(3, 3)).unwrap();
-        } else {
-            dest = GenericSurface::new(&mut dest_array_scaled[..], size(6, 6)).unwrap();
-        }
+        let mut dest_buf = alloc::vec![0_u8; desired.len()];
+        let mut dest = GenericSurface::new(&mut dest_buf[..], desired.surface_size()).unwrap();
 
         blit(&mut dest, src.surface(), &transforms);
 
@@ -227,6 +262,30 @@ fn transforms() {
     }
 }
 
+#[test]
+fn asymmetric_upscale() {
+    let src = [1_u8, 2, 3, 4];
+    let mut dest = [0_u8; 24];
+
+    blit(
+        GenericSurface::new(&mut dest, size(4, 6)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        &[Transform::UpScale { x: 2, y: 3 }],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 1, 2, 2,
+        1, 1, 2, 2,
+        1, 1, 2, 2,
+        3, 3, 4, 4,
+        3, 3, 4, 4,
+        3, 3, 4, 4,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
 #[test]
 fn simple() {
     let mut dest = [0_u8; 25];
@@ -265,11 +324,10 @@ fn too_small() {
 
     let src_buf = GenericSurface::new(&src, size(4, 4)).unwrap();
 
-    blit(
-        dest_buf,
-        src_buf.sub_surface(point(0, 0), size(6, 6)),
-        Default::default(),
-    );
+    let src_buf = src_buf.sub_surface(point(0, 0), size(6, 6));
+    assert_eq!(src_buf.surface_size(), size(4, 4));
+
+    blit(dest_buf, src_buf, Default::default());
 
     #[rustfmt::skip]
     let correct: [u8; 25] = [
@@ -308,3 +366,3202 @@ fn test_subsurface() {
 
     assert_eq!(dest, correct);
 }
+
+#[cfg(feature = "rgb")]
+#[test]
+fn blend() {
+    use rgb::RGBA8;
+
+    let mut dest = [RGBA8::new(100, 100, 100, 255); 3];
+
+    let src = [
+        RGBA8::new(200, 0, 50, 255),
+        RGBA8::new(200, 0, 50, 0),
+        RGBA8::new(200, 0, 50, 128),
+    ];
+
+    crate::blit_blend(
+        GenericSurface::new(&mut dest, size(3, 1)).unwrap(),
+        GenericSurface::new(&src, size(3, 1)).unwrap(),
+        &[],
+    );
+
+    assert_eq!(
+        dest,
+        [
+            RGBA8::new(200, 0, 50, 255),
+            RGBA8::new(100, 100, 100, 255),
+            RGBA8::new(150, 50, 75, 255),
+        ]
+    );
+}
+
+#[test]
+fn positions() {
+    let mut dest = [(0, 0); 6];
+
+    let src = [0_u8; 6];
+
+    blit_with_positions(
+        GenericSurface::new(&mut dest, size(3, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 3)).unwrap(),
+        &[Transform::Rotate90Cw],
+        |dest, _, dest_pos, src_pos| {
+            *dest = (dest_pos.x * 10 + dest_pos.y, src_pos.x * 10 + src_pos.y);
+        },
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        (0, 2), (10, 1), (20, 0),
+        (1, 12), (11, 11), (21, 10),
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn rectangular_transforms() {
+    let transforms = prop::collection::vec(
+        prop::sample::select(
+            &[
+                Transform::FlipHorizontal,
+                Transform::FlipVertical,
+                Transform::FlipBoth,
+                Transform::Rotate90Cw,
+                Transform::Rotate90Ccw,
+                Transform::Rotate180,
+                Transform::Transpose,
+                Transform::UpScale { x: 2, y: 1 },
+                Transform::UpScale { x: 1, y: 3 },
+            ][..],
+        ),
+        0..=8,
+    );
+
+    let mut runner = TestRunner::new(Config::with_cases(2_000));
+
+    let result = runner.run(&(0..5_u32, 0..5_u32, transforms), |(w, h, transforms)| {
+        let src = [0_u8; 16];
+        let src = GenericSurface::new(&src[..(w * h) as usize], size(w, h)).unwrap();
+
+        let positions = |transforms: &[Transform]| {
+            let dest_size = Transform::transform_size_chain(transforms, size(w, h));
+            let mut dest = alloc::vec![None; (dest_size.x * dest_size.y) as usize];
+
+            blit_with_positions(
+                GenericSurface::new(&mut dest, dest_size).unwrap(),
+                src,
+                transforms,
+                |dest, _, _, src_pos| *dest = Some(src_pos),
+            );
+
+            dest
+        };
+
+        // a downscale by 1 changes nothing, but the positions can't be precomputed with it
+        let mut unsimplified = transforms.clone();
+        unsimplified.push(Transform::DownScale { x: 1, y: 1 });
+
+        prop_assert_eq!(positions(&transforms), positions(&unsimplified));
+
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => {}
+        Err(error) => {
+            panic!("{error}");
+        }
+    }
+}
+
+#[test]
+fn try_abort() {
+    let mut dest = [0_u8; 9];
+
+    let src = [1_u8, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    let result = try_blit_with(
+        GenericSurface::new(&mut dest, size(3, 3)).unwrap(),
+        GenericSurface::new(&src, size(3, 3)).unwrap(),
+        &[],
+        |dest, src, pos| {
+            if pos == point(1, 1) {
+                Err(*src)
+            } else {
+                *dest = *src;
+                Ok(())
+            }
+        },
+    );
+
+    assert_eq!(result, Err(5));
+    assert_eq!(dest, [1, 2, 3, 4, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn count() {
+    let mut dest = [0_u8; 25];
+
+    let src = [1_u8; 16];
+
+    let count = blit_with_count(
+        GenericSurface::new(&mut dest, size(5, 5))
+            .unwrap()
+            .offset_surface_mut(point(3, 2)),
+        GenericSurface::new(&src, size(4, 4)).unwrap(),
+        &[],
+        |dest, src, _| *dest = *src,
+    );
+
+    assert_eq!(count, 6);
+    assert_eq!(dest.iter().filter(|&&v| v == 1).count(), 6);
+}
+
+#[test]
+fn masked_by() {
+    let mut dest = [9_u8; 6];
+
+    let src = [0_u8, 1, 2, 3, 4, 5];
+
+    blit_masked_by(
+        GenericSurface::new(&mut dest, size(3, 2)).unwrap(),
+        GenericSurface::new(&src, size(3, 2)).unwrap(),
+        &[],
+        |&v| !(1..=3).contains(&v),
+    );
+
+    assert_eq!(dest, [0, 9, 9, 9, 4, 5]);
+}
+
+#[cfg(feature = "num-traits")]
+#[test]
+fn saturating_add() {
+    let mut dest = [0_u8, 100, 200, 250];
+
+    let src = [10_u8, 100, 100, 10];
+
+    crate::blit_saturating_add(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        &[],
+    );
+
+    assert_eq!(dest, [10, 200, 255, 255]);
+}
+
+#[test]
+fn bitwise() {
+    let src = [0b1100_u8; 4];
+
+    let mut dest = [0b1010_u8; 4];
+    blit_bitor(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        &[],
+    );
+    assert_eq!(dest, [0b1110; 4]);
+
+    let mut dest = [0b1010_u8; 4];
+    blit_bitand(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        &[],
+    );
+    assert_eq!(dest, [0b1000; 4]);
+
+    let mut dest = [0b1010_u8; 4];
+    blit_bitxor(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        &[],
+    );
+    assert_eq!(dest, [0b0110; 4]);
+}
+
+#[test]
+fn flip_views() {
+    let src = Predefined::TopLeft(size(1, 1)).surface();
+
+    let mut dest = [0_u8; 9];
+    blit(
+        GenericSurface::new(&mut dest, size(3, 3)).unwrap(),
+        (&src).flip_x(),
+        &[],
+    );
+    assert_eq!(&dest[..], &*Predefined::TopRight(size(1, 1)).surface());
+
+    let mut dest = [0_u8; 9];
+    blit(
+        GenericSurface::new(&mut dest, size(3, 3)).unwrap().flip_y(),
+        &src,
+        &[],
+    );
+    assert_eq!(&dest[..], &*Predefined::BottomLeft(size(1, 1)).surface());
+}
+
+#[test]
+fn transpose_view() {
+    let src = [1_u8, 2, 3, 4, 5, 6];
+
+    let mut dest = [0_u8; 6];
+
+    blit(
+        GenericSurface::new(&mut dest, size(2, 3)).unwrap(),
+        GenericSurface::new(&src, size(3, 2)).unwrap().transpose(),
+        &[],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 4,
+        2, 5,
+        3, 6,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn fn_surface() {
+    let gradient = [0_u8, 85, 170, 255];
+
+    let mut dest = [0_u8; 8];
+
+    blit(
+        GenericSurface::new(&mut dest, size(4, 2)).unwrap(),
+        FnSurface::new(size(4, 2), |pt| &gradient[pt.x as usize]),
+        &[],
+    );
+
+    assert_eq!(dest, [0, 85, 170, 255, 0, 85, 170, 255]);
+}
+
+#[test]
+fn tiled() {
+    let src = [1_u8, 2, 3, 4];
+
+    let mut dest = [0_u8; 15];
+
+    blit(
+        GenericSurface::new(&mut dest, size(5, 3)).unwrap(),
+        GenericSurface::new(&src, size(2, 2))
+            .unwrap()
+            .tiled(size(5, 3)),
+        &[],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 2, 1, 2, 1,
+        3, 4, 3, 4, 3,
+        1, 2, 1, 2, 1,
+    ];
+
+    assert_eq!(dest, correct);
+
+    let empty: [u8; 0] = [];
+    let empty = GenericSurface::new(&empty, size(0, 0)).unwrap();
+    assert_eq!(empty.tiled(size(5, 3)).surface_get(point(1, 1)), None);
+}
+
+#[test]
+fn map_view() {
+    let palette = [0x000000_u32, 0xFF0000, 0x00FF00, 0x0000FF];
+
+    let src = [0_u8, 1, 2, 3, 3, 2];
+
+    let mut dest = [0_u32; 6];
+
+    blit(
+        GenericSurface::new(&mut dest, size(3, 2)).unwrap(),
+        GenericSurface::new(&src, size(3, 2))
+            .unwrap()
+            .map(|&idx| &palette[idx as usize]),
+        &[],
+    );
+
+    assert_eq!(
+        dest,
+        [0x000000, 0xFF0000, 0x00FF00, 0x0000FF, 0x0000FF, 0x00FF00]
+    );
+}
+
+#[test]
+fn concat() {
+    let a = [1_u8; 4];
+    let b = [2_u8; 2];
+    let c = [3_u8; 3];
+
+    let a = GenericSurface::new(&a, size(2, 2)).unwrap();
+    let b = GenericSurface::new(&b, size(1, 2)).unwrap();
+    let c = GenericSurface::new(&c, size(3, 1)).unwrap();
+
+    assert!(ConcatH::new(a, c).is_none());
+
+    let sheet = ConcatV::new(ConcatH::new(a, b).unwrap(), c).unwrap();
+    assert_eq!(sheet.surface_size(), size(3, 3));
+
+    let mut dest = [0_u8; 9];
+    blit(
+        GenericSurface::new(&mut dest, size(3, 3)).unwrap(),
+        sheet,
+        &[],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 1, 2,
+        1, 1, 2,
+        3, 3, 3,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn concat_overflow() {
+    let wide = SingleValueSurface::new(1_u8, size(u32::MAX, 1));
+    let tall = SingleValueSurface::new(1_u8, size(1, u32::MAX));
+
+    assert!(ConcatH::new(wide, wide).is_none());
+    assert!(ConcatV::new(tall, tall).is_none());
+
+    let half = SingleValueSurface::new(1_u8, size(u32::MAX / 2, 1));
+    assert_eq!(
+        ConcatH::new(
+            half,
+            SingleValueSurface::new(1_u8, size(u32::MAX - u32::MAX / 2, 1))
+        )
+        .unwrap()
+        .surface_size(),
+        size(u32::MAX, 1)
+    );
+}
+
+#[test]
+fn padded() {
+    let src = [1_u8; 2];
+
+    let padded = PadSurface::new(
+        GenericSurface::new(&src, size(2, 1)).unwrap(),
+        size(1, 0),
+        size(0, 1),
+        9,
+    );
+    assert_eq!(padded.surface_size(), size(3, 2));
+    assert_eq!(padded.surface_get(point(3, 0)), None);
+
+    let huge = PadSurface::uniform(SingleValueSurface::new(1_u8, size(u32::MAX, 1)), 1, 0);
+    assert_eq!(huge.surface_size(), size(u32::MAX, 3));
+    assert_eq!(huge.surface_get(point(0, 1)), Some(&0));
+    assert_eq!(huge.surface_get(point(u32::MAX - 1, 1)), Some(&1));
+
+    let mut dest = [0_u8; 6];
+    blit(
+        GenericSurface::new(&mut dest, size(3, 2)).unwrap(),
+        padded,
+        &[],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        9, 1, 1,
+        9, 9, 9,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn checker() {
+    let mut dest = [0_u8; 12];
+
+    blit(
+        GenericSurface::new(&mut dest, size(4, 3)).unwrap(),
+        CheckerSurface::new(size(4, 3), size(2, 1), 1, 2),
+        &[],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 1, 2, 2,
+        2, 2, 1, 1,
+        1, 1, 2, 2,
+    ];
+
+    assert_eq!(dest, correct);
+
+    let huge = CheckerSurface::new(size(u32::MAX, u32::MAX), size(1, 1), 1_u8, 2);
+    assert_eq!(
+        huge.surface_get(point(u32::MAX - 1, u32::MAX - 1)),
+        Some(&1)
+    );
+    assert_eq!(
+        huge.surface_get(point(u32::MAX - 1, u32::MAX - 2)),
+        Some(&2)
+    );
+}
+
+#[test]
+fn stride() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2, 0, 0,
+        3, 4, 0, 0,
+        5, 6,
+    ];
+
+    assert!(GenericSurface::with_stride(&src[..9], size(2, 3), 4).is_none());
+    assert!(GenericSurface::with_stride(&src, size(2, 3), 1).is_none());
+
+    let src = GenericSurface::with_stride(&src, size(2, 3), 4).unwrap();
+
+    let mut dest = [0_u8; 6];
+    blit(
+        GenericSurface::new(&mut dest, size(2, 3)).unwrap(),
+        src,
+        &[],
+    );
+
+    assert_eq!(dest, [1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn array_surface() {
+    let mut dest = ArraySurface::<u8, 3, 2>::new([[0; 3]; 2]);
+
+    blit(
+        &mut dest,
+        ArraySurface::new([[1_u8, 2], [3, 4]]),
+        &[Transform::FlipHorizontal],
+    );
+
+    assert_eq!(dest.into_inner(), [[2, 1, 0], [4, 3, 0]]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn owned() {
+    let mut dest = GenericSurface::new_default(size(3, 2));
+    assert_eq!(&*dest, &[0_u8; 6]);
+
+    blit(&mut dest, GenericSurface::new_filled(size(2, 2), 7_u8), &[]);
+
+    assert_eq!(&*dest, &[7, 7, 0, 7, 7, 0]);
+}
+
+#[cfg(all(feature = "alloc", target_pointer_width = "32"))]
+#[test]
+#[should_panic(expected = "surface size overflows usize")]
+fn owned_size_overflow() {
+    let _ = GenericSurface::<alloc::vec::Vec<u8>, u8>::new_filled(size(65536, 65536), 0);
+}
+
+#[test]
+fn subsurface_clamped() {
+    let src = [1_u8; 16];
+
+    let src_buf = GenericSurface::new(&src, size(4, 4)).unwrap();
+
+    assert_eq!(
+        src_buf.sub_surface(point(3, 1), size(2, 2)).surface_size(),
+        size(1, 2)
+    );
+    assert_eq!(
+        src_buf.sub_surface(point(5, 5), size(2, 2)).surface_size(),
+        size(0, 0)
+    );
+}
+
+#[test]
+fn offset_outside() {
+    let mut dest = [0_u8; 4];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(2, 2)).unwrap();
+    let dest_buf = dest_buf.offset_surface_mut(point(3, 1));
+    assert_eq!(dest_buf.surface_size(), size(0, 1));
+
+    blit(dest_buf, SingleValueSurface::new(1_u8, size(2, 2)), &[]);
+
+    assert_eq!(dest, [0; 4]);
+}
+
+#[test]
+fn size_overflow() {
+    let empty: [u8; 0] = [];
+    assert!(GenericSurface::new(&empty, size(65536, 65536)).is_none());
+
+    let short = [0_u8; 65536];
+    assert!(GenericSurface::new(&short, size(65536, 65537)).is_none());
+}
+
+#[test]
+fn infer() {
+    let src = [0_u8; 12];
+
+    assert_eq!(
+        GenericSurface::new_infer(&src, 4).unwrap().surface_size(),
+        size(4, 3)
+    );
+    assert!(GenericSurface::new_infer(&src, 5).is_none());
+    assert!(GenericSurface::new_infer(&src, 0).is_none());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "invalid transform UpScale { x: 0, y: 2 }")]
+fn zero_scale() {
+    let mut dest = [0_u8; 4];
+
+    blit(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        SingleValueSurface::new(1_u8, size(2, 2)),
+        &[Transform::FlipVertical, Transform::UpScale { x: 0, y: 2 }],
+    );
+}
+
+#[test]
+fn transform_chain() {
+    let transforms = [Transform::Rotate90Cw, Transform::UpScale { x: 2, y: 3 }];
+
+    assert_eq!(
+        Transform::transform_size_chain(&transforms, size(4, 2)),
+        size(4, 12)
+    );
+    assert_eq!(
+        Transform::transform_point_chain(&transforms, point(3, 0), size(4, 2)),
+        point(2, 9)
+    );
+}
+
+#[test]
+fn downscale_uneven() {
+    let src = [1_u8, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    let mut dest = [0_u8; 1];
+
+    blit(
+        GenericSurface::new(&mut dest, size(1, 1)).unwrap(),
+        GenericSurface::new(&src, size(3, 3)).unwrap(),
+        &[Transform::FlipBoth, Transform::DownScale { x: 2, y: 2 }],
+    );
+
+    assert_eq!(dest, [9]);
+}
+
+#[test]
+fn transpose_transform() {
+    let src = [1_u8, 2, 3, 4, 5, 6];
+
+    let mut dest = [0_u8; 6];
+
+    blit(
+        GenericSurface::new(&mut dest, size(2, 3)).unwrap(),
+        GenericSurface::new(&src, size(3, 2)).unwrap(),
+        &[Transform::Transpose],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 4,
+        2, 5,
+        3, 6,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn simplify() {
+    let transforms = prop::collection::vec(
+        prop::strategy::Union::new([
+            prop::sample::select(
+                &[
+                    Transform::FlipHorizontal,
+                    Transform::FlipVertical,
+                    Transform::FlipBoth,
+                    Transform::Rotate90Cw,
+                    Transform::Rotate90Ccw,
+                    Transform::Rotate180,
+                    Transform::Transpose,
+                ][..],
+            )
+            .boxed(),
+            (1..=3_u32, 1..=3_u32)
+                .prop_map(|(x, y)| Transform::UpScale { x, y })
+                .boxed(),
+        ]),
+        0..=12,
+    );
+
+    let sources = prop::sample::select(alloc::vec![
+        Predefined::TopLeft(size(1, 1)),
+        Predefined::TopRight(size(1, 1)),
+        Predefined::BottomLeft(size(1, 1)),
+        Predefined::BottomRight(size(1, 1)),
+    ]);
+
+    let cases = (sources, transforms);
+
+    let mut runner = TestRunner::new(Config::with_cases(2_000));
+
+    let result = runner.run(&cases, |(src, transforms)| {
+        let simplified = Transform::simplify(&transforms).unwrap();
+        prop_assert!(simplified.len() <= 3);
+
+        let desired = transforms
+            .iter()
+            .copied()
+            .fold(src, |src, tr| src.transform(tr));
+        let desired_simplified = simplified
+            .iter()
+            .copied()
+            .fold(src, |src, tr| src.transform(tr));
+
+        prop_assert_eq!(desired, desired_simplified);
+
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => {}
+        Err(error) => {
+            panic!("{error}");
+        }
+    }
+
+    assert_eq!(
+        &*Transform::simplify(&[
+            Transform::UpScale { x: 1, y: 2 },
+            Transform::Rotate90Cw,
+            Transform::Rotate90Cw,
+            Transform::Rotate90Cw,
+            Transform::UpScale { x: 3, y: 1 },
+        ])
+        .unwrap(),
+        &[Transform::Rotate90Ccw, Transform::UpScale { x: 6, y: 1 }]
+    );
+    assert_eq!(
+        Transform::simplify(&[Transform::DownScale { x: 2, y: 2 }]),
+        None
+    );
+}
+
+#[test]
+fn inverse() {
+    let transforms = [
+        Transform::Rotate90Cw,
+        Transform::UpScale { x: 2, y: 3 },
+        Transform::FlipHorizontal,
+        Transform::Transpose,
+    ];
+    let inverted = Transform::invert_chain(&transforms)
+        .collect::<Option<Vec<_>>>()
+        .unwrap();
+
+    let src_size = size(4, 2);
+    let dest_size = Transform::transform_size_chain(&transforms, src_size);
+    assert_eq!(
+        Transform::transform_size_chain(&inverted, dest_size),
+        src_size
+    );
+
+    for y in 0..src_size.y {
+        for x in 0..src_size.x {
+            let dest_pt = Transform::transform_point_chain(&transforms, point(x, y), src_size);
+
+            assert_eq!(
+                Transform::transform_point_chain(&inverted, dest_pt, dest_size),
+                point(x, y)
+            );
+        }
+    }
+
+    assert_eq!(Transform::DownScale { x: 2, y: 2 }.inverse(), None);
+}
+
+#[test]
+fn pixels() {
+    #[rustfmt::skip]
+    let mut src = [
+        1, 2, 0,
+        3, 4,
+    ];
+
+    let mut src = GenericSurface::with_stride(&mut src, size(2, 2), 3).unwrap();
+
+    for (pt, value) in src.pixels_mut() {
+        *value += pt.x as u8 * 10;
+    }
+
+    let pixels = src
+        .sub_surface(point(0, 0), size(2, 2))
+        .pixels()
+        .map(|(pt, &value)| (pt.x, pt.y, value))
+        .collect::<Vec<_>>();
+
+    assert_eq!(pixels, [(0, 0, 1), (1, 0, 12), (0, 1, 3), (1, 1, 14)]);
+}
+
+#[test]
+fn rows() {
+    #[rustfmt::skip]
+    let mut src = [
+        1, 2, 0,
+        3, 4, 0,
+        5, 6,
+    ];
+
+    let mut src = GenericSurface::with_stride(&mut src, size(2, 3), 3).unwrap();
+
+    for row in src.rows_mut() {
+        row.reverse();
+    }
+
+    let rows = src.rows().collect::<Vec<_>>();
+    assert_eq!(rows, [[2, 1], [4, 3], [6, 5]]);
+
+    let mut empty: [u8; 0] = [];
+    let mut empty = GenericSurface::new(&mut empty, size(0, 3)).unwrap();
+    assert_eq!(empty.rows_mut().count(), 3);
+    assert!(empty.rows().all(|row| row.is_empty()));
+    assert_eq!(empty.rows().count(), 3);
+}
+
+#[test]
+fn fill() {
+    let mut dest = [0_u8; 12];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(4, 3)).unwrap();
+    dest_buf.fill(1);
+    dest_buf
+        .sub_surface_mut(point(1, 1), size(2, 5))
+        .fill_with(|pt| pt.x as u8 + 2);
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 1, 1, 1,
+        1, 2, 3, 1,
+        1, 2, 3, 1,
+    ];
+
+    assert_eq!(dest, correct);
+
+    // `SurfaceMut::fill` takes precedence over the slice method
+    let mut dest = [0_u8; 5];
+
+    let mut dest_buf = GenericSurface::with_stride(&mut dest, size(2, 2), 3).unwrap();
+    dest_buf.fill(1);
+    assert_eq!(dest_buf[..], [1, 1, 0, 1, 1]);
+    dest_buf[..].fill(2);
+    assert_eq!(dest, [2; 5]);
+}
+
+#[test]
+fn get_by_coords() {
+    let mut dest = [1_u8, 2, 3, 4, 5, 6];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(3, 2)).unwrap();
+
+    assert_eq!(dest_buf.get(2, 0), Some(&3));
+    assert_eq!(dest_buf.get(3, 0), None);
+    assert!(dest_buf.contains(point(0, 1)));
+    assert!(!dest_buf.contains(point(0, 2)));
+
+    *dest_buf.get_mut(1, 1).unwrap() = 9;
+
+    // `Surface::get` and friends take precedence over the slice methods
+    assert_eq!(<[u8]>::get(&dest_buf, 1), Some(&2));
+    assert!(dest_buf[..].contains(&9));
+    assert_eq!(dest, [1, 2, 3, 4, 9, 6]);
+}
+
+#[test]
+fn index() {
+    let mut dest = [1_u8, 2, 3, 4, 5, 6];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(3, 2)).unwrap();
+    assert_eq!(dest_buf[point(2, 1)], 6);
+    assert_eq!(dest_buf[4], 5);
+
+    // slice indices still work like through `Deref`
+    assert_eq!(&dest_buf[1..3], &[2, 3]);
+    assert_eq!(&dest_buf[4..], &[5, 6]);
+    assert_eq!(&dest_buf[..=1], &[1, 2]);
+    assert_eq!(&dest_buf[(Bound::Excluded(0), Bound::Included(2))], &[2, 3]);
+    dest_buf[..][0] = 7;
+    let all: &mut [u8] = &mut dest_buf[..];
+    all.reverse();
+    assert_eq!(&dest_buf[..], &[6, 5, 4, 3, 2, 7]);
+    dest_buf[..].reverse();
+
+    dest_buf.sub_surface_mut(point(1, 1), size(2, 1))[point(0, 0)] = 9;
+    assert_eq!(dest, [7, 2, 3, 4, 9, 6]);
+}
+
+#[test]
+#[should_panic(expected = "point out of bounds")]
+fn index_out_of_bounds() {
+    let dest = [0_u8; 6];
+
+    let _ = GenericSurface::new(&dest, size(3, 2)).unwrap()[point(3, 0)];
+}
+
+#[test]
+fn copy_within() {
+    #[rustfmt::skip]
+    let mut dest = [
+        1, 2, 3, 0,
+        4, 5, 6, 0,
+        7, 8, 9, 0,
+        0, 0, 0, 0,
+    ];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(4, 4)).unwrap();
+    dest_buf.copy_within(point(0, 0), point(1, 1), size(3, 3));
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 2, 3, 0,
+        4, 1, 2, 3,
+        7, 4, 5, 6,
+        0, 7, 8, 9,
+    ];
+
+    assert_eq!(dest, correct);
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(4, 4)).unwrap();
+    dest_buf.copy_within(point(1, 1), point(0, 0), size(3, 3));
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 2, 3, 0,
+        4, 5, 6, 3,
+        7, 8, 9, 6,
+        0, 7, 8, 9,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn swap() {
+    #[derive(Debug, PartialEq)]
+    struct NoClone(u8);
+
+    let mut dest = [NoClone(1), NoClone(2), NoClone(3), NoClone(4)];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(2, 2)).unwrap();
+    assert!(dest_buf.swap(point(0, 0), point(1, 1)));
+    assert!(!dest_buf.swap(point(0, 0), point(2, 1)));
+    // the inherent method takes precedence over the slice method
+    dest_buf[..].swap(1, 2);
+    assert_eq!(dest, [NoClone(4), NoClone(3), NoClone(2), NoClone(1)]);
+
+    let mut dest = [1_u8, 2, 3, 4];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(2, 2)).unwrap();
+    let mut dest_buf = dest_buf.sub_surface_mut(point(0, 1), size(2, 1));
+    assert!(dest_buf.swap(point(0, 0), point(1, 0)));
+    assert!(!dest_buf.swap(point(0, 0), point(0, 1)));
+    assert_eq!(dest, [1, 2, 4, 3]);
+}
+
+#[test]
+fn lines() {
+    use crate::{draw_line, signed_point};
+
+    fn draw(from: (i32, i32), to: (i32, i32)) -> [u8; 20] {
+        let mut dest = [0_u8; 20];
+
+        draw_line(
+            GenericSurface::new(&mut dest, size(5, 4)).unwrap(),
+            signed_point(from.0, from.1),
+            signed_point(to.0, to.1),
+            1,
+        );
+
+        dest
+    }
+
+    #[rustfmt::skip]
+    assert_eq!(draw((-2, 1), (3, 1)), [
+        0, 0, 0, 0, 0,
+        1, 1, 1, 1, 0,
+        0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(draw((2, 3), (2, -5)), [
+        0, 0, 1, 0, 0,
+        0, 0, 1, 0, 0,
+        0, 0, 1, 0, 0,
+        0, 0, 1, 0, 0,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(draw((0, 0), (3, 3)), [
+        1, 0, 0, 0, 0,
+        0, 1, 0, 0, 0,
+        0, 0, 1, 0, 0,
+        0, 0, 0, 1, 0,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(draw((4, 0), (3, 3)), [
+        0, 0, 0, 0, 1,
+        0, 0, 0, 0, 1,
+        0, 0, 0, 1, 0,
+        0, 0, 0, 1, 0,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(draw((0, 3), (4, 1)), [
+        0, 0, 0, 0, 0,
+        0, 0, 0, 1, 1,
+        0, 1, 1, 0, 0,
+        1, 0, 0, 0, 0,
+    ]);
+
+    // far away endpoints are clipped instead of walked
+    #[rustfmt::skip]
+    assert_eq!(draw((-2_000_000_000, 2), (2_000_000_000, 2)), [
+        0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0,
+        1, 1, 1, 1, 1,
+        0, 0, 0, 0, 0,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(draw((i32::MIN, i32::MIN), (i32::MAX, i32::MAX)), [
+        1, 0, 0, 0, 0,
+        0, 1, 0, 0, 0,
+        0, 0, 1, 0, 0,
+        0, 0, 0, 1, 0,
+    ]);
+
+    assert_eq!(draw((i32::MIN, 0), (i32::MIN, i32::MAX)), [0; 20]);
+}
+
+#[test]
+fn clipped_lines() {
+    use crate::{draw_line, signed_point};
+
+    // walks the whole line, like `draw_line` did before clipping
+    fn reference(dest: &mut [u8; 80], (x0, y0): (i64, i64), (x1, y1): (i64, i64)) {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let step_x = if x0 < x1 { 1 } else { -1 };
+        let step_y = if y0 < y1 { 1 } else { -1 };
+
+        let (mut x, mut y) = (x0, y0);
+        let mut error = dx + dy;
+
+        loop {
+            if (0..10).contains(&x) && (0..8).contains(&y) {
+                dest[(y * 10 + x) as usize] = 1;
+            }
+
+            if x == x1 && y == y1 {
+                break;
+            }
+
+            let error2 = 2 * error;
+
+            if error2 >= dy {
+                error += dy;
+                x += step_x;
+            }
+
+            if error2 <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    let coord = || -40..50_i32;
+    let cases = ((coord(), coord()), (coord(), coord()));
+    let mut runner = TestRunner::new(Config::with_cases(10_000));
+
+    let result = runner.run(&cases, |(from, to)| {
+        let mut dest = [0_u8; 80];
+        draw_line(
+            GenericSurface::new(&mut dest, size(10, 8)).unwrap(),
+            signed_point(from.0, from.1),
+            signed_point(to.0, to.1),
+            1,
+        );
+
+        let mut desired = [0_u8; 80];
+        reference(
+            &mut desired,
+            (from.0.into(), from.1.into()),
+            (to.0.into(), to.1.into()),
+        );
+
+        prop_assert_eq!(dest, desired);
+
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => {}
+        Err(error) => {
+            panic!("{error}");
+        }
+    }
+}
+
+#[test]
+fn rects() {
+    use crate::{draw_rect, fill_rect};
+
+    let mut dest = [0_u8; 20];
+
+    let mut dest_buf = GenericSurface::new(&mut dest, size(5, 4)).unwrap();
+    fill_rect(&mut dest_buf, point(3, 2), size(10, 10), 2);
+    draw_rect(&mut dest_buf, point(0, 0), size(3, 3), 1);
+    draw_rect(&mut dest_buf, point(4, 0), size(1, 1), 3);
+
+    #[rustfmt::skip]
+    let correct = [
+        1, 1, 1, 0, 3,
+        1, 0, 1, 0, 0,
+        1, 1, 1, 2, 2,
+        0, 0, 0, 2, 2,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn circles() {
+    use crate::{draw_circle, fill_circle};
+
+    let mut dest = [0_u8; 25];
+    draw_circle(
+        GenericSurface::new(&mut dest, size(5, 5)).unwrap(),
+        point(2, 2),
+        2,
+        1,
+    );
+
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        0, 1, 1, 1, 0,
+        1, 0, 0, 0, 1,
+        1, 0, 0, 0, 1,
+        1, 0, 0, 0, 1,
+        0, 1, 1, 1, 0,
+    ]);
+
+    let mut dest = [0_u8; 25];
+    fill_circle(
+        GenericSurface::new(&mut dest, size(5, 5)).unwrap(),
+        point(0, 1),
+        2,
+        1,
+    );
+
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        1, 1, 1, 0, 0,
+        1, 1, 1, 0, 0,
+        1, 1, 1, 0, 0,
+        1, 1, 0, 0, 0,
+        0, 0, 0, 0, 0,
+    ]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn flood() {
+    #[rustfmt::skip]
+    let mut dest = [
+        0, 0, 0, 0, 0, 0,
+        0, 1, 1, 1, 1, 0,
+        0, 1, 0, 0, 1, 0,
+        0, 1, 0, 1, 1, 0,
+        0, 1, 1, 0, 0, 0,
+    ];
+
+    crate::flood_fill(
+        GenericSurface::new(&mut dest, size(6, 5)).unwrap(),
+        point(2, 2),
+        2,
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        0, 0, 0, 0, 0, 0,
+        0, 1, 1, 1, 1, 0,
+        0, 1, 2, 2, 1, 0,
+        0, 1, 2, 1, 1, 0,
+        0, 1, 1, 0, 0, 0,
+    ];
+
+    assert_eq!(dest, correct);
+
+    crate::flood_fill(
+        GenericSurface::new(&mut dest, size(6, 5)).unwrap(),
+        point(5, 4),
+        3,
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        3, 3, 3, 3, 3, 3,
+        3, 1, 1, 1, 1, 3,
+        3, 1, 2, 2, 1, 3,
+        3, 1, 2, 1, 1, 3,
+        3, 1, 1, 3, 3, 3,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn row_fast_path() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2, 3, 0,
+        4, 5, 6, 0,
+        7, 8, 9,
+    ];
+    let src = GenericSurface::with_stride(&src, size(3, 3), 4).unwrap();
+
+    assert_eq!(src.surface_row(1), Some(&[4, 5, 6][..]));
+    assert_eq!(src.surface_row(3), None);
+    assert_eq!(src.surface_row(u32::MAX), None);
+    assert_eq!(
+        src.sub_surface(point(1, 1), size(2, 2)).surface_row(1),
+        Some(&[8, 9][..])
+    );
+
+    let mut fast = [0_u8; 16];
+    let mut slow = [0_u8; 16];
+
+    blit(
+        GenericSurface::new(&mut fast, size(4, 4))
+            .unwrap()
+            .into_offset_surface(point(2, 1)),
+        src,
+        &[Transform::FlipBoth, Transform::Rotate180],
+    );
+    crate::blit_with(
+        GenericSurface::new(&mut slow, size(4, 4))
+            .unwrap()
+            .into_offset_surface(point(2, 1)),
+        src,
+        &[],
+        |dest, src, _| *dest = *src,
+    );
+
+    assert_eq!(fast, slow);
+
+    #[rustfmt::skip]
+    assert_eq!(fast, [
+        0, 0, 0, 0,
+        0, 0, 1, 2,
+        0, 0, 4, 5,
+        0, 0, 7, 8,
+    ]);
+}
+
+#[test]
+fn exact() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2, 0,
+        3, 4, 0,
+    ];
+    let src = GenericSurface::with_stride(&src, size(2, 2), 3).unwrap();
+
+    let mut dest = [0_u8; 4];
+    blit_exact(GenericSurface::new(&mut dest, size(2, 2)).unwrap(), src);
+    assert_eq!(dest, [1, 2, 3, 4]);
+
+    let mut dest = ArraySurface::new([[0_u8; 2]; 2]);
+    blit_exact(&mut dest, src.flip_x());
+    assert_eq!(dest.into_inner(), [[2, 1], [4, 3]]);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "blit_exact called with surfaces of different sizes")]
+fn exact_mismatched() {
+    let mut dest = [0_u8; 6];
+
+    blit_exact(
+        GenericSurface::new(&mut dest, size(3, 2)).unwrap(),
+        SingleValueSurface::new(1_u8, size(2, 2)),
+    );
+}
+
+#[cfg(not(debug_assertions))]
+#[test]
+fn exact_mismatched() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2,
+        3, 4,
+        5, 6,
+    ];
+
+    let mut dest = [0_u8; 6];
+    blit_exact(
+        GenericSurface::new(&mut dest, size(3, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 3)).unwrap(),
+    );
+    assert_eq!(dest, [1, 2, 0, 3, 4, 0]);
+
+    let mut dest = [0_u8; 2];
+    blit_exact(
+        GenericSurface::new(&mut dest, size(1, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 3)).unwrap(),
+    );
+    assert_eq!(dest, [1, 3]);
+}
+
+#[cfg(feature = "image-integration")]
+#[test]
+fn image_convert() {
+    use image::{Luma, Pixel, Rgb, RgbImage, Rgba, RgbaImage};
+
+    let mut background = RgbImage::from_pixel(2, 1, Rgb([0, 0, 200]));
+    let mut sprite = RgbaImage::new(2, 1);
+    sprite.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+    sprite.put_pixel(1, 0, Rgba([255, 0, 0, 0]));
+
+    crate::blit_image_convert(&mut background, &sprite, &[]);
+
+    assert_eq!(background.get_pixel(0, 0), &Rgb([255, 0, 0]));
+    assert_eq!(background.get_pixel(1, 0), &Rgb([0, 0, 200]));
+
+    let mut mask = image::GrayImage::new(2, 1);
+    crate::blit_image_convert(&mut mask, &background, &[Transform::FlipHorizontal]);
+
+    assert_eq!(mask.get_pixel(0, 0), &Rgb([0, 0, 200]).to_luma());
+    assert_eq!(mask.get_pixel(1, 0), &Luma([54]));
+}
+
+#[cfg(feature = "image-integration")]
+#[test]
+fn dynamic_image() {
+    use image::{DynamicImage, Rgb, RgbImage, Rgba};
+
+    let mut image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([1, 2, 3])));
+
+    let src = crate::to_rgba8_surface(&image);
+    assert_eq!(src.surface_get(point(1, 1)), Some(&Rgba([1, 2, 3, 255])));
+    drop(src);
+
+    let dest = crate::as_rgba8_surface_mut(&mut image);
+    blit(
+        dest,
+        SingleValueSurface::new(Rgba([4, 5, 6, 7]), size(1, 1)),
+        &[],
+    );
+
+    let buffer = image.as_rgba8().unwrap();
+    assert_eq!(buffer.get_pixel(0, 0), &Rgba([4, 5, 6, 7]));
+    assert!(matches!(
+        crate::to_rgba8_surface(&image),
+        alloc::borrow::Cow::Borrowed(_)
+    ));
+}
+
+#[cfg(feature = "embedded-graphics-integration")]
+#[test]
+fn embedded_graphics() {
+    use embedded_graphics_core::{
+        draw_target::DrawTarget,
+        geometry::{Dimensions, Point as EgPoint, Size as EgSize},
+        pixelcolor::BinaryColor,
+        primitives::Rectangle,
+        Pixel,
+    };
+
+    let mut dest = [BinaryColor::Off; 6];
+    let mut surface = GenericSurface::new(&mut dest, size(3, 2)).unwrap();
+
+    assert_eq!(
+        surface.bounding_box(),
+        Rectangle::new(EgPoint::zero(), EgSize::new(3, 2))
+    );
+
+    surface
+        .draw_iter([
+            Pixel(EgPoint::new(1, 0), BinaryColor::On),
+            Pixel(EgPoint::new(-1, 1), BinaryColor::On),
+            Pixel(EgPoint::new(3, 1), BinaryColor::On),
+        ])
+        .unwrap();
+    surface
+        .fill_solid(
+            &Rectangle::new(EgPoint::new(2, 1), EgSize::new(5, 5)),
+            BinaryColor::On,
+        )
+        .unwrap();
+
+    use BinaryColor::{Off, On};
+    assert_eq!(dest, [Off, On, Off, Off, Off, On]);
+}
+
+#[cfg(feature = "ndarray-integration")]
+#[test]
+fn ndarray() {
+    use alloc::vec;
+    use ndarray::{array, s, Array2};
+
+    let src = array![[1_u8, 2, 3], [4, 5, 6]];
+    assert_eq!(src.surface_size(), size(3, 2));
+    assert_eq!(src.surface_get(point(2, 0)), Some(&3));
+    assert_eq!(src.surface_row(1), Some(&[4, 5, 6][..]));
+    assert_eq!(src.t().surface_row(1), None);
+
+    let mut dest = Array2::<u8>::zeros((4, 4));
+
+    // every other column, so the rows aren't contiguous
+    blit(
+        dest.slice_mut(s![1.., ..;2]),
+        src.view(),
+        &[Transform::Rotate90Cw],
+    );
+
+    assert_eq!(
+        dest,
+        array![[0, 0, 0, 0], [4, 0, 1, 0], [5, 0, 2, 0], [6, 0, 3, 0]]
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+fn glam_conversions() {
+    let pt: crate::Point = glam::UVec2::new(1, 2).into();
+    assert_eq!(pt, point(1, 2));
+
+    let vec: glam::UVec2 = size(3, 4).into();
+    assert_eq!(vec, glam::UVec2::new(3, 4));
+}
+
+#[cfg(feature = "euclid")]
+#[test]
+fn euclid_conversions() {
+    use euclid::default::{Point2D, Size2D};
+
+    let pt: crate::Point = Point2D::new(1, 2).into();
+    assert_eq!(pt, point(1, 2));
+
+    let sz: Size2D<u32> = size(3, 4).into();
+    assert_eq!(sz, Size2D::new(3, 4));
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn from_bytes() {
+    let mut bytes = [1_u8, 2, 3, 4, 5, 6, 7, 8];
+
+    let surface = GenericSurface::<&[[u8; 4]], _>::from_bytes(&bytes, size(2, 1)).unwrap();
+    assert_eq!(surface.surface_get(point(1, 0)), Some(&[5, 6, 7, 8]));
+
+    assert!(GenericSurface::<&[[u8; 4]], _>::from_bytes(&bytes[..7], size(2, 1)).is_none());
+    assert!(GenericSurface::<&[[u8; 4]], _>::from_bytes(&bytes, size(1, 1)).is_none());
+
+    let mut surface =
+        GenericSurface::<&mut [[u8; 2]], _>::from_bytes_mut(&mut bytes, size(2, 2)).unwrap();
+    surface.fill([0, 9]);
+
+    assert_eq!(bytes, [0, 9, 0, 9, 0, 9, 0, 9]);
+
+    // u32 needs 4-byte alignment
+    let words = [0_u32; 3];
+    let bytes: &[u8] = bytemuck::cast_slice(&words);
+    assert!(GenericSurface::<&[u32], _>::from_bytes(&bytes[1..9], size(2, 1)).is_none());
+    assert!(GenericSurface::<&[u32], _>::from_bytes(&bytes[4..12], size(2, 1)).is_some());
+}
+
+#[cfg(feature = "softbuffer-integration")]
+#[test]
+fn softbuffer_color() {
+    assert_eq!(crate::softbuffer_color(0x12, 0x34, 0x56), 0x00123456);
+}
+
+#[test]
+fn scaled() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2,
+        3, 4,
+    ];
+    let src = GenericSurface::new(&src, size(2, 2)).unwrap();
+
+    let mut dest = [0_u8; 16];
+    blit_scaled(
+        GenericSurface::new(&mut dest, size(4, 4))
+            .unwrap()
+            .into_offset_surface(point(1, 0)),
+        src,
+        size(3, 5),
+    );
+
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        0, 1, 1, 2,
+        0, 1, 1, 2,
+        0, 1, 1, 2,
+        0, 3, 3, 4,
+    ]);
+
+    // downscaling skips values
+    let src = [1_u8, 2, 3, 4, 5, 6, 7];
+    let mut dest = [0_u8; 3];
+    blit_scaled(
+        GenericSurface::new(&mut dest, size(3, 1)).unwrap(),
+        GenericSurface::new(&src, size(7, 1)).unwrap(),
+        size(3, 1),
+    );
+
+    assert_eq!(dest, [1, 3, 5]);
+}
+
+#[cfg(feature = "num-traits")]
+#[test]
+fn bilinear() {
+    let src = [0_u8, 100];
+    let mut dest = [0_u8; 4];
+
+    crate::blit_bilinear(
+        GenericSurface::new(&mut dest, size(4, 1)).unwrap(),
+        GenericSurface::new(&src, size(2, 1)).unwrap(),
+        size(4, 1),
+    );
+
+    assert_eq!(dest, [0, 25, 75, 100]);
+
+    #[rustfmt::skip]
+    let src = [
+        0.0, 1.0,
+        2.0, 3.0_f32,
+    ];
+    let mut dest = [0.0_f32; 9];
+
+    crate::blit_bilinear(
+        GenericSurface::new(&mut dest, size(3, 3)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        size(3, 3),
+    );
+
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        0.0, 0.5, 1.0,
+        1.0, 1.5, 2.0,
+        2.0, 2.5, 3.0,
+    ]);
+
+    // the same size copies the values
+    let src = [[1_u8, 2], [3, 4], [5, 6]];
+    let mut dest = [[0_u8; 2]; 3];
+
+    crate::blit_bilinear(
+        GenericSurface::new(&mut dest, size(3, 1)).unwrap(),
+        GenericSurface::new(&src, size(3, 1)).unwrap(),
+        size(3, 1),
+    );
+
+    assert_eq!(dest, src);
+}
+
+#[test]
+fn edge_clamped() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2,
+        3, 4,
+    ];
+    let src = GenericSurface::new(&src, size(2, 2))
+        .unwrap()
+        .edge_clamped();
+
+    assert_eq!(src.surface_size(), size(2, 2));
+    assert_eq!(src.surface_get(point(1, 0)), Some(&2));
+    assert_eq!(src.surface_get(point(5, 0)), Some(&2));
+    assert_eq!(src.surface_get(point(7, 9)), Some(&4));
+    assert_eq!(src.get_clamped(crate::signed_point(-3, 1)), Some(&3));
+    assert_eq!(src.get_clamped(crate::signed_point(1, -1)), Some(&2));
+
+    let empty = EdgeClampSurface::new(SingleValueSurface::new(0_u8, size(0, 3)));
+    assert_eq!(empty.surface_get(point(0, 0)), None);
+}
+
+#[test]
+fn wrapping() {
+    #[rustfmt::skip]
+    let mut src = [
+        1, 2, 3,
+        4, 5, 6,
+    ];
+    let mut src = GenericSurface::new(&mut src, size(3, 2))
+        .unwrap()
+        .wrapping();
+
+    assert_eq!(src.surface_size(), size(3, 2));
+    assert_eq!(src.surface_get(point(4, 0)), Some(&2));
+    assert_eq!(src.surface_get(point(6, 3)), Some(&4));
+    assert_eq!(src.get_wrapped(crate::signed_point(-1, -1)), Some(&6));
+    assert_eq!(src.get_wrapped(crate::signed_point(-4, 2)), Some(&3));
+
+    *src.get_wrapped_mut(crate::signed_point(-3, -2)).unwrap() = 0;
+    *src.surface_get_mut(point(5, 1)).unwrap() = 0;
+
+    assert_eq!(*src.into_inner().into_inner(), [0, 2, 3, 4, 5, 0]);
+}
+
+#[test]
+fn zip_with() {
+    let a = [10_u8, 20, 30, 40];
+    let b = [30_u8, 40, 50];
+    let mut dest = [0_u8; 4];
+
+    crate::blit_zip_with(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        GenericSurface::new(&a, size(2, 2)).unwrap(),
+        GenericSurface::new(&b, size(3, 1)).unwrap(),
+        &[Transform::Rotate90Cw],
+        |dest, a, b, _| *dest = (a + b) / 2,
+    );
+
+    // the sources are clipped to 2x1, which becomes 1x2 after the rotation
+    assert_eq!(dest, [20, 0, 30, 0]);
+}
+
+#[test]
+fn zip() {
+    let a = [1_u8, 2, 3, 4];
+    let b = [1_u8, 5, 3, 0];
+    let a = GenericSurface::new(&a, size(2, 2)).unwrap();
+    let b = GenericSurface::new(&b, size(2, 2)).unwrap();
+
+    assert!(a.zip(b.sub_surface(point(0, 0), size(2, 1))).is_none());
+
+    let zipped = a.zip(b).unwrap();
+    assert_eq!(zipped.get(point(1, 0)), Some((&2, &5)));
+    assert_eq!(zipped.get(point(2, 0)), None);
+
+    let different = zipped
+        .pixels()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(pt, _)| pt)
+        .collect::<Vec<_>>();
+
+    assert_eq!(different, [point(1, 0), point(1, 1)]);
+}
+
+#[test]
+fn diff() {
+    #[rustfmt::skip]
+    let a = [
+        1, 2, 3,
+        4, 5, 6,
+        7, 8, 9,
+    ];
+    #[rustfmt::skip]
+    let b = [
+        1, 2, 3,
+        4, 0, 6,
+        7, 8, 0,
+    ];
+    let a = GenericSurface::new(&a, size(3, 3)).unwrap();
+    let b = GenericSurface::new(&b, size(3, 3)).unwrap();
+
+    assert_eq!(crate::diff_bounds(a, a), None);
+    assert_eq!(crate::diff_bounds(a, b), Some((point(1, 1), size(2, 2))));
+
+    // the extra column only exists in `a`
+    let cropped = a.sub_surface(point(0, 0), size(2, 3));
+    assert_eq!(
+        crate::diff_bounds(a, cropped),
+        Some((point(2, 0), size(1, 3)))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn to_owned() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2, 0,
+        3, 4, 0,
+        5, 6,
+    ];
+
+    let owned = GenericSurface::with_stride(&src[..], size(2, 3), 3)
+        .unwrap()
+        .to_owned();
+    assert_eq!(owned.surface_size(), size(2, 3));
+    assert_eq!(&*owned, &[1, 2, 3, 4, 5, 6]);
+
+    let cropped = owned
+        .sub_surface(point(1, 1), size(5, 2))
+        .to_owned()
+        .unwrap();
+    assert_eq!(cropped.surface_size(), size(1, 2));
+    assert_eq!(&*cropped, &[4, 6]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cropped() {
+    #[rustfmt::skip]
+    let src = [
+        1, 2, 3, 0,
+        4, 5, 6, 0,
+        7, 8, 9,
+    ];
+    let src = GenericSurface::with_stride(&src, size(3, 3), 4).unwrap();
+
+    let cropped = src.cropped(point(1, 1), size(2, 2)).unwrap();
+    assert_eq!(cropped.surface_size(), size(2, 2));
+    assert_eq!(cropped.stride(), 2);
+    assert_eq!(&*cropped, &[5, 6, 8, 9]);
+
+    assert!(src.cropped(point(3, 0), size(0, 3)).unwrap().is_empty());
+    assert!(src.cropped(point(2, 0), size(2, 1)).is_none());
+    assert!(src.cropped(point(0, u32::MAX), size(1, 2)).is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_validated() {
+    let src = [1_u8, 2, 3, 0, 4, 5, 6];
+    let surface = GenericSurface::with_stride(&src[..], size(3, 2), 4).unwrap();
+
+    let json = serde_json::to_string(&surface).unwrap();
+    assert_eq!(json, r#"{"slice":[1,2,3,0,4,5,6],"size":[3,2],"stride":4}"#);
+
+    let parsed: GenericSurface<Vec<u8>, u8> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.surface_get(point(2, 1)), Some(&6));
+    assert_eq!(parsed.stride(), 4);
+
+    let invalid = serde_json::from_str::<GenericSurface<Vec<u8>, u8>>(
+        r#"{"slice":[1,2,3],"size":[3,2],"stride":3}"#,
+    );
+    assert!(invalid.is_err());
+
+    let invalid = serde_json::from_str::<GenericSurface<Vec<u8>, u8>>(
+        r#"{"slice":[1,2,3,4],"size":[2,2],"stride":1}"#,
+    );
+    assert!(invalid.is_err());
+
+    // trailing values would otherwise be exposed through `Deref` and `into_inner`
+    let too_long = serde_json::from_str::<GenericSurface<Vec<u8>, u8>>(
+        r#"{"slice":[1,2,3,0,4,5,6,7],"size":[3,2],"stride":4}"#,
+    );
+    assert!(too_long.is_err());
+
+    let too_short = serde_json::from_str::<GenericSurface<Vec<u8>, u8>>(
+        r#"{"slice":[1,2,3,0,4,5],"size":[3,2],"stride":4}"#,
+    );
+    assert!(too_short.is_err());
+
+    // the stride is optional
+    let packed: GenericSurface<Vec<u8>, u8> =
+        serde_json::from_str(r#"{"slice":[1,2,3,4,5,6],"size":[3,2]}"#).unwrap();
+    assert_eq!(packed.stride(), 3);
+    assert_eq!(packed.surface_get(point(0, 1)), Some(&4));
+
+    let too_long = serde_json::from_str::<GenericSurface<Vec<u8>, u8>>(
+        r#"{"slice":[1,2,3,4,5,6,7],"size":[3,2]}"#,
+    );
+    assert!(too_long.is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_sub_surface() {
+    let src = GenericSurface::new(alloc::vec![1_u8, 2, 3, 4, 5, 6], size(3, 2)).unwrap();
+    let sub = src.into_sub_surface(point(1, 0), size(2, 2));
+
+    let json = serde_json::to_string(&sub).unwrap();
+    let parsed: crate::SubSurface<GenericSurface<Vec<u8>, u8>, u8> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, sub);
+
+    let invalid = json.replace(r#""offset":[1,0]"#, r#""offset":[2,0]"#);
+    assert_ne!(invalid, json);
+    assert!(
+        serde_json::from_str::<crate::SubSurface<GenericSurface<Vec<u8>, u8>, u8>>(&invalid)
+            .is_err()
+    );
+}
+
+#[test]
+fn blit_all() {
+    let sprite = [1_u8, 2];
+    let sprite = GenericSurface::new(&sprite, size(2, 1)).unwrap();
+    let mut dest = [0_u8; 9];
+
+    crate::blit_all(
+        GenericSurface::new(&mut dest, size(3, 3)).unwrap(),
+        [
+            (point(0, 0), sprite, &[][..]),
+            (point(1, 0), sprite, &[Transform::Rotate90Cw][..]),
+            (point(2, 2), sprite, &[Transform::FlipHorizontal][..]),
+            (point(5, 5), sprite, &[][..]),
+        ],
+    );
+
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        1, 1, 0,
+        0, 2, 0,
+        0, 0, 2,
+    ]);
+}
+
+#[cfg(feature = "rgb")]
+#[test]
+fn tinted() {
+    use rgb::RGBA8;
+
+    let src = [
+        RGBA8::new(200, 100, 50, 255),
+        RGBA8::new(255, 255, 255, 128),
+    ];
+    let mut dest = [RGBA8::new(0, 0, 0, 255); 2];
+
+    crate::blit_tinted(
+        GenericSurface::new(&mut dest, size(2, 1)).unwrap(),
+        GenericSurface::new(&src, size(2, 1)).unwrap(),
+        &[],
+        RGBA8::new(255, 128, 0, 255),
+    );
+
+    assert_eq!(dest[0], RGBA8::new(200, 50, 0, 255));
+    assert_eq!(dest[1], RGBA8::new(128, 64, 0, 255));
+
+    // a white opaque tint is the same as `blit_blend`
+    let mut tinted = [RGBA8::new(10, 20, 30, 40); 2];
+    let mut blended = tinted;
+
+    crate::blit_tinted(
+        GenericSurface::new(&mut tinted, size(2, 1)).unwrap(),
+        GenericSurface::new(&src, size(2, 1)).unwrap(),
+        &[],
+        RGBA8::new(255, 255, 255, 255),
+    );
+    crate::blit_blend(
+        GenericSurface::new(&mut blended, size(2, 1)).unwrap(),
+        GenericSurface::new(&src, size(2, 1)).unwrap(),
+        &[],
+    );
+
+    assert_eq!(tinted, blended);
+}
+
+#[cfg(feature = "rgb")]
+#[test]
+fn grayscale() {
+    use rgb::RGBA8;
+
+    let src = [
+        RGBA8::new(255, 0, 0, 255),
+        RGBA8::new(0, 255, 0, 100),
+        RGBA8::new(0, 0, 255, 0),
+        RGBA8::new(200, 200, 200, 50),
+    ];
+    let mut dest = [RGBA8::default(); 4];
+
+    crate::blit_grayscale(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        &[],
+    );
+
+    assert_eq!(
+        dest,
+        [
+            RGBA8::new(76, 76, 76, 255),
+            RGBA8::new(150, 150, 150, 100),
+            RGBA8::new(29, 29, 29, 0),
+            RGBA8::new(200, 200, 200, 50),
+        ]
+    );
+
+    crate::blit_grayscale_weighted(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        GenericSurface::new(&src, size(2, 2)).unwrap(),
+        &[],
+        [1., 1., 0.],
+    );
+
+    assert_eq!(
+        dest,
+        [
+            RGBA8::new(255, 255, 255, 255),
+            RGBA8::new(255, 255, 255, 100),
+            RGBA8::new(0, 0, 0, 0),
+            RGBA8::new(255, 255, 255, 50),
+        ]
+    );
+}
+
+#[test]
+fn surface_error() {
+    use crate::SurfaceError;
+
+    let data = [0u8; 6];
+
+    assert_eq!(
+        GenericSurface::try_new(&data[..], size(2, 2)),
+        Err(SurfaceError::LengthMismatch {
+            expected: 4,
+            got: 6
+        })
+    );
+    assert!(GenericSurface::try_new(&data[..], size(2, 3)).is_ok());
+    #[cfg(target_pointer_width = "32")]
+    assert_eq!(
+        GenericSurface::try_new(&data[..], size(u32::MAX, u32::MAX)),
+        Err(SurfaceError::Overflow)
+    );
+
+    assert_eq!(
+        GenericSurface::try_new_infer(&data[..], 0),
+        Err(SurfaceError::ZeroWidth)
+    );
+    assert_eq!(
+        GenericSurface::try_new_infer(&data[..], 4),
+        Err(SurfaceError::LengthMismatch {
+            expected: 8,
+            got: 6
+        })
+    );
+    assert_eq!(
+        GenericSurface::try_new_infer(&data[..], 3).map(|surface| surface.surface_size()),
+        Ok(size(3, 2))
+    );
+}
+
+#[test]
+fn size_helpers() {
+    use crate::{area, clamp_point, size_contains};
+
+    assert_eq!(area(size(3, 4)), 12);
+    assert_eq!(
+        area(size(u32::MAX, u32::MAX)),
+        u32::MAX as u64 * u32::MAX as u64
+    );
+
+    assert!(size_contains(size(3, 4), point(2, 3)));
+    assert!(!size_contains(size(3, 4), point(3, 0)));
+    assert!(!size_contains(size(0, 4), point(0, 0)));
+
+    assert_eq!(clamp_point(point(5, 1), size(3, 4)), point(2, 1));
+    assert_eq!(clamp_point(point(1, 9), size(3, 4)), point(1, 3));
+    assert_eq!(clamp_point(point(5, 5), size(0, 0)), point(0, 0));
+}
+
+#[test]
+fn fold_pixels() {
+    let data = [1u8, 2, 3, 4, 5, 6];
+    let surface = GenericSurface::new(&data[..], size(3, 2)).unwrap();
+
+    assert_eq!(
+        surface.fold_pixels(0u32, |sum, _, &value| sum + value as u32),
+        21
+    );
+    assert_eq!(
+        surface.fold_pixels(0u32, |sum, pt, _| sum + pt.x + pt.y * 10),
+        36
+    );
+    assert_eq!(surface.count_where(|&value| value % 2 == 0), 3);
+    assert_eq!(
+        surface
+            .sub_surface(point(1, 1), size(2, 1))
+            .count_where(|_| true),
+        2
+    );
+}
+
+#[test]
+fn mirrored() {
+    use crate::signed_point;
+
+    let data = [1u8, 2, 3, 4, 5, 6];
+    let surface = GenericSurface::new(&data[..], size(3, 2))
+        .unwrap()
+        .mirrored();
+
+    assert_eq!(surface.surface_size(), size(3, 2));
+
+    let row: Vec<u8> = (-4..7)
+        .map(|x| *surface.get_mirrored(signed_point(x, 0)).unwrap())
+        .collect();
+    assert_eq!(row, [3, 3, 2, 1, 1, 2, 3, 3, 2, 1, 1]);
+
+    assert_eq!(surface.get_mirrored(signed_point(-1, -1)), Some(&1));
+    assert_eq!(surface.get_mirrored(signed_point(1, 2)), Some(&5));
+    assert_eq!(surface.get_mirrored(signed_point(1, 3)), Some(&2));
+    assert_eq!(surface.surface_get(point(4, 2)), Some(&5));
+    assert_eq!(
+        surface.get_mirrored(signed_point(i32::MIN, i32::MAX)),
+        Some(&2)
+    );
+
+    // mirroring a sub-surface reflects at its own edges
+    let inner = GenericSurface::new(&data[..], size(3, 2))
+        .unwrap()
+        .into_sub_surface(point(1, 0), size(2, 2))
+        .mirrored();
+    assert_eq!(inner.get_mirrored(signed_point(-1, 0)), Some(&2));
+    assert_eq!(inner.get_mirrored(signed_point(2, 1)), Some(&6));
+    assert_eq!(inner.get_mirrored(signed_point(3, -1)), Some(&2));
+
+    let empty = MirrorSurface::new(SingleValueSurface::new(0_u8, size(3, 0)));
+    assert_eq!(empty.get_mirrored(signed_point(1, 1)), None);
+}
+
+#[cfg(all(feature = "num-traits", feature = "alloc"))]
+#[test]
+fn convolve_separable() {
+    use crate::convolve_separable;
+
+    #[rustfmt::skip]
+    let data = [
+        0u8, 0, 0, 0,
+        0, 90, 0, 0,
+        0, 0, 0, 30,
+    ];
+    let src = GenericSurface::new(&data[..], size(4, 3)).unwrap();
+    let mut dest = [0u8; 12];
+
+    // identity
+    convolve_separable(
+        GenericSurface::new(&mut dest[..], size(4, 3)).unwrap(),
+        src.edge_clamped(),
+        &[1.],
+        &[1.],
+    );
+    assert_eq!(dest, data);
+
+    let blur = [1. / 3., 1. / 3., 1. / 3.];
+
+    convolve_separable(
+        GenericSurface::new(&mut dest[..], size(4, 3)).unwrap(),
+        src.edge_clamped(),
+        &blur,
+        &blur,
+    );
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        10, 10, 10, 0,
+        10, 10, 13, 7,
+        10, 10, 17, 13,
+    ]);
+
+    convolve_separable(
+        GenericSurface::new(&mut dest[..], size(4, 3)).unwrap(),
+        src.wrapping(),
+        &blur,
+        &[1.],
+    );
+    assert_eq!(dest[8..], [10, 0, 10, 10]);
+
+    // negative results saturate
+    convolve_separable(
+        GenericSurface::new(&mut dest[..], size(4, 3)).unwrap(),
+        src.mirrored(),
+        &[-1., 2., -1.],
+        &[1.],
+    );
+    assert_eq!(dest[4..8], [0, 180, 0, 0]);
+}
+
+#[cfg(feature = "num-traits")]
+#[test]
+fn downscale_avg() {
+    use crate::blit_downscale_avg;
+
+    #[rustfmt::skip]
+    let data = [
+        0u8, 10, 100, 200, 7,
+        20, 30, 50, 51, 7,
+        9, 9, 9, 9, 9,
+    ];
+    let src = GenericSurface::new(&data[..], size(5, 3)).unwrap();
+    let mut dest = [0u8; 4];
+
+    blit_downscale_avg(
+        GenericSurface::new(&mut dest[..], size(2, 2)).unwrap(),
+        src,
+        size(2, 2),
+    );
+    // the partial blocks are clipped
+    assert_eq!(dest, [15, 100, 0, 0]);
+
+    blit_downscale_avg(
+        GenericSurface::new(&mut dest[..], size(4, 1)).unwrap(),
+        src,
+        size(1, 3),
+    );
+    assert_eq!(dest, [10, 16, 53, 87]);
+
+    let floats = [1.0f32, 2.0, 4.0, 8.0];
+    let mut dest = [0.0f32];
+
+    blit_downscale_avg(
+        GenericSurface::new(&mut dest[..], size(1, 1)).unwrap(),
+        GenericSurface::new(&floats[..], size(4, 1)).unwrap(),
+        size(4, 1),
+    );
+    assert_eq!(dest, [3.75]);
+}
+
+#[test]
+fn transform_list() {
+    use crate::TransformList;
+
+    let mut list = TransformList::new();
+    assert!(list.is_empty());
+
+    list.push(Transform::Rotate90Cw);
+    list.push(Transform::FlipHorizontal);
+    assert_eq!(*list, [Transform::Rotate90Cw, Transform::FlipHorizontal]);
+
+    let data = [1u8, 2, 3, 4];
+    let mut from_list = [0u8; 4];
+    let mut from_slice = [0u8; 4];
+    blit(
+        GenericSurface::new(&mut from_list[..], size(2, 2)).unwrap(),
+        GenericSurface::new(&data[..], size(2, 2)).unwrap(),
+        &list,
+    );
+    blit(
+        GenericSurface::new(&mut from_slice[..], size(2, 2)).unwrap(),
+        GenericSurface::new(&data[..], size(2, 2)).unwrap(),
+        &[Transform::Rotate90Cw, Transform::FlipHorizontal],
+    );
+    assert_eq!(from_list, from_slice);
+
+    assert_eq!(list.pop(), Some(Transform::FlipHorizontal));
+    assert_eq!(list, {
+        let mut other = TransformList::new();
+        other.push(Transform::Rotate90Cw);
+        other
+    });
+
+    list.clear();
+    for _ in 0..TransformList::CAPACITY {
+        assert_eq!(list.try_push(Transform::Transpose), Ok(()));
+    }
+    assert_eq!(
+        list.try_push(Transform::Rotate180),
+        Err(Transform::Rotate180)
+    );
+    assert_eq!(list.len(), TransformList::CAPACITY);
+}
+
+#[test]
+#[should_panic(expected = "transform list is full")]
+fn transform_list_overflow() {
+    let mut list = crate::TransformList::new();
+
+    for _ in 0..=crate::TransformList::CAPACITY {
+        list.push(Transform::Transpose);
+    }
+}
+
+#[test]
+fn checked() {
+    use crate::{blit_checked, Clipped, Coverage};
+
+    let data = [1u8; 6];
+    let src = GenericSurface::new(&data[..], size(3, 2)).unwrap();
+    let mut dest = [0u8; 16];
+    let mut dest = GenericSurface::new(&mut dest[..], size(4, 4)).unwrap();
+
+    assert_eq!(blit_checked(&mut dest, src, &[]), Ok(Coverage::Full));
+    assert_eq!(
+        blit_checked(dest.offset_surface_mut(point(2, 1)), src, &[]),
+        Ok(Coverage::Partial { written: 4 })
+    );
+    assert_eq!(
+        blit_checked(
+            dest.offset_surface_mut(point(1, 0)),
+            src,
+            &[Transform::UpScale { x: 1, y: 2 }]
+        ),
+        Ok(Coverage::Full)
+    );
+    assert_eq!(
+        blit_checked(dest.offset_surface_mut(point(4, 0)), src, &[]),
+        Err(Clipped)
+    );
+    assert_eq!(
+        blit_checked(&mut dest, src.sub_surface(point(0, 0), size(0, 2)), &[]),
+        Err(Clipped)
+    );
+}
+
+#[test]
+fn blit_at() {
+    use crate::{blit_at, signed_point};
+
+    let data = [1u8, 2, 3, 4, 5, 6];
+    let src = GenericSurface::new(&data[..], size(3, 2)).unwrap();
+
+    for transforms in [
+        &[][..],
+        &[Transform::Rotate90Cw],
+        &[Transform::FlipHorizontal, Transform::UpScale { x: 2, y: 1 }],
+    ] {
+        // the whole transformed source, to compare against
+        let transformed_size = Transform::transform_size_chain(transforms, src.surface_size());
+        let mut transformed = [0u8; 64];
+        let mut transformed = GenericSurface::new(&mut transformed[..], size(8, 8)).unwrap();
+        blit(&mut transformed, src, transforms);
+
+        // every position that puts the source on an edge or corner of the destination, or outside of it
+        for y in -5..=5 {
+            for x in -7..=5 {
+                let mut dest = [0u8; 16];
+                blit_at(
+                    GenericSurface::new(&mut dest[..], size(4, 4)).unwrap(),
+                    signed_point(x, y),
+                    src,
+                    transforms,
+                );
+
+                for dy in 0..4 {
+                    for dx in 0..4 {
+                        let (sx, sy) = (dx - x, dy - y);
+                        let expected = if (0..transformed_size.x as i32).contains(&sx)
+                            && (0..transformed_size.y as i32).contains(&sy)
+                        {
+                            transformed[point(sx as u32, sy as u32)]
+                        } else {
+                            0
+                        };
+
+                        assert_eq!(
+                            dest[(dy * 4 + dx) as usize],
+                            expected,
+                            "{transforms:?} at ({x}, {y}), dest ({dx}, {dy})"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut dest = [0u8; 4];
+    blit_at(
+        GenericSurface::new(&mut dest[..], size(2, 2)).unwrap(),
+        signed_point(-1, -1),
+        src,
+        &[],
+    );
+    assert_eq!(dest, [5, 6, 0, 0]);
+}
+
+#[cfg(feature = "libm")]
+#[test]
+fn rotated() {
+    use crate::blit_rotated;
+    use core::f32::consts::{FRAC_PI_2, PI};
+
+    #[rustfmt::skip]
+    let data = [
+        1u8, 2, 3,
+        4, 5, 6,
+        7, 8, 9,
+        10, 11, 12,
+        13, 14, 15,
+    ];
+    let src = GenericSurface::new(&data[..], size(3, 5)).unwrap();
+
+    // right angles match the exact transforms
+    for (angle, transforms) in [
+        (0.0, &[][..]),
+        (FRAC_PI_2, &[Transform::Rotate90Cw]),
+        (PI, &[Transform::Rotate180]),
+        (-FRAC_PI_2, &[Transform::Rotate90Ccw]),
+    ] {
+        let mut rotated = [0u8; 49];
+        let mut exact = [0u8; 49];
+
+        blit_rotated(
+            GenericSurface::new(&mut rotated[..], size(7, 7)).unwrap(),
+            src,
+            point(3, 3),
+            angle,
+        );
+
+        let exact_size = Transform::transform_size_chain(transforms, size(3, 5));
+        blit(
+            GenericSurface::new(&mut exact[..], size(7, 7))
+                .unwrap()
+                .offset_surface_mut(point(3 - exact_size.x / 2, 3 - exact_size.y / 2)),
+            src,
+            transforms,
+        );
+
+        assert_eq!(rotated, exact, "angle {angle}");
+    }
+
+    // clipped at the destination corner
+    let mut dest = [0u8; 4];
+    blit_rotated(
+        GenericSurface::new(&mut dest[..], size(2, 2)).unwrap(),
+        src,
+        point(0, 0),
+        0.0,
+    );
+    assert_eq!(dest, [8, 9, 11, 12]);
+
+    // 45 degrees covers a diamond
+    let square = SingleValueSurface::new(1u8, size(3, 3));
+    let mut dest = [0u8; 25];
+    blit_rotated(
+        GenericSurface::new(&mut dest[..], size(5, 5)).unwrap(),
+        square,
+        point(2, 2),
+        PI / 4.0,
+    );
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        0, 0, 1, 0, 0,
+        0, 1, 1, 1, 0,
+        1, 1, 1, 1, 1,
+        0, 1, 1, 1, 0,
+        0, 0, 1, 0, 0,
+    ]);
+}
+
+#[test]
+fn nested_arrays() {
+    let src = [[1u8, 2, 3], [4, 5, 6]];
+    let mut dest = [[0u8; 2]; 3];
+
+    assert_eq!(src.surface_size(), size(3, 2));
+    assert_eq!(src.surface_get(point(2, 1)), Some(&6));
+    assert_eq!(src.surface_get(point(3, 0)), None);
+    assert_eq!(src.surface_row(1), Some(&[4, 5, 6][..]));
+
+    blit(&mut dest, src, &[Transform::Rotate90Cw]);
+    assert_eq!(dest, [[4, 1], [5, 2], [6, 3]]);
+
+    *dest.surface_get_mut(point(1, 2)).unwrap() = 9;
+    assert_eq!(dest[2], [6, 9]);
+    assert_eq!(ArraySurface::new(dest).surface_get(point(1, 2)), Some(&9));
+
+    // the surface methods take precedence over the array methods
+    assert_eq!(dest.get(1, 2), Some(&9));
+    assert_eq!(dest[..].get(1), Some(&[5, 2]));
+    assert!(dest.contains(point(1, 2)));
+    assert!(dest.swap(point(0, 0), point(1, 2)));
+    dest.fill(7);
+    assert_eq!(dest, [[7; 2]; 3]);
+}
+
+#[cfg(feature = "minifb-integration")]
+#[test]
+fn minifb_surface() {
+    use crate::{minifb_color, minifb_surface};
+
+    assert_eq!(minifb_color(0x12, 0x34, 0x56), 0x00123456);
+
+    let mut buffer = [0u32; 6];
+    let mut surface = minifb_surface(&mut buffer, 3, 2).unwrap();
+    surface.fill(minifb_color(255, 0, 255));
+    assert_eq!(buffer, [0x00ff00ff; 6]);
+
+    assert!(minifb_surface(&mut buffer, 4, 2).is_none());
+    assert!(minifb_surface(&mut [], usize::MAX, 0).is_none());
+}
+
+#[test]
+fn color_key() {
+    use crate::blit_color_key;
+
+    let src = [1u8, 0, 2, 0];
+    let mut dest = [9u8; 4];
+
+    blit_color_key(
+        GenericSurface::new(&mut dest[..], size(2, 2)).unwrap(),
+        GenericSurface::new(&src[..], size(2, 2)).unwrap(),
+        &[],
+        0,
+    );
+    assert_eq!(dest, [1, 9, 2, 9]);
+}
+
+#[cfg(feature = "rgb")]
+#[test]
+fn color_key_tolerance() {
+    use crate::{blit_color_key, blit_color_key_tolerance};
+    use rgb::RGBA8;
+
+    let key = RGBA8::new(255, 0, 255, 0);
+    let src = [
+        key,
+        RGBA8::new(255, 0, 255, 255),
+        RGBA8::new(250, 3, 252, 2),
+        RGBA8::new(240, 0, 255, 0),
+    ];
+    let background = RGBA8::new(1, 2, 3, 4);
+
+    let mut dest = [background; 4];
+    blit_color_key(
+        GenericSurface::new(&mut dest[..], size(4, 1)).unwrap(),
+        GenericSurface::new(&src[..], size(4, 1)).unwrap(),
+        &[],
+        key,
+    );
+    assert_eq!(dest, [background, src[1], src[2], src[3]]);
+
+    let mut dest = [background; 4];
+    blit_color_key_tolerance(
+        GenericSurface::new(&mut dest[..], size(4, 1)).unwrap(),
+        GenericSurface::new(&src[..], size(4, 1)).unwrap(),
+        &[],
+        key,
+        5,
+    );
+    assert_eq!(dest, [background, src[1], background, src[3]]);
+}
+
+#[test]
+fn tiles() {
+    #[rustfmt::skip]
+    let data = [
+        1u8, 1, 2, 2, 3,
+        1, 1, 2, 2, 3,
+        4, 4, 5, 5, 6,
+    ];
+    let sheet = GenericSurface::new(&data[..], size(5, 3)).unwrap();
+
+    let tiles: Vec<_> = sheet.tiles(size(2, 2)).collect();
+    assert_eq!(tiles.len(), 2);
+    for (tile, value) in tiles.iter().zip([1, 2]) {
+        assert_eq!(tile.surface_size(), size(2, 2));
+        assert!(tile.pixels().all(|(_, &v)| v == value));
+    }
+
+    let columns: Vec<u8> = sheet
+        .tiles(size(1, 3))
+        .map(|tile| *tile.surface_get(point(0, 2)).unwrap())
+        .collect();
+    assert_eq!(columns, [4, 4, 5, 5, 6]);
+
+    assert_eq!(
+        sheet
+            .tile(1, 0, size(2, 2))
+            .unwrap()
+            .surface_get(point(1, 1)),
+        Some(&2)
+    );
+    assert_eq!(
+        sheet
+            .tile(4, 2, size(1, 1))
+            .unwrap()
+            .surface_get(point(0, 0)),
+        Some(&6)
+    );
+    assert!(sheet.tile(2, 0, size(2, 2)).is_none());
+    assert!(sheet.tile(0, 0, size(0, 2)).is_none());
+    assert!(sheet.tile(u32::MAX, 0, size(2, 2)).is_none());
+    assert_eq!(sheet.tiles(size(0, 1)).count(), 0);
+    assert_eq!(sheet.tiles(size(6, 1)).count(), 0);
+}
+
+#[test]
+fn dest_bounds() {
+    use crate::{blit_at, signed_point};
+
+    assert_eq!(
+        Transform::dest_bounds(&[Transform::Rotate90Cw], size(3, 5)),
+        (size(5, 3), signed_point(-1, 1))
+    );
+    assert_eq!(
+        Transform::dest_bounds(&[Transform::Rotate180], size(3, 5)),
+        (size(3, 5), signed_point(0, 0))
+    );
+    assert_eq!(
+        Transform::dest_bounds(&[Transform::Transpose], size(2, 3)),
+        (size(3, 2), signed_point(-1, 0))
+    );
+    assert_eq!(
+        Transform::dest_bounds(
+            &[Transform::UpScale { x: 3, y: 1 }, Transform::Rotate90Ccw],
+            size(1, 1)
+        ),
+        (size(1, 3), signed_point(0, -1))
+    );
+
+    // rotating around the center keeps the middle value in place
+    let data = [1u8, 2, 3];
+    let src = GenericSurface::new(&data[..], size(1, 3)).unwrap();
+    let pos = signed_point(1, 0);
+    let (_, correction) = Transform::dest_bounds(&[Transform::Rotate90Cw], src.surface_size());
+
+    let mut dest = [0u8; 9];
+    blit_at(
+        GenericSurface::new(&mut dest[..], size(3, 3)).unwrap(),
+        signed_point(pos.x + correction.x, pos.y + correction.y),
+        src,
+        &[Transform::Rotate90Cw],
+    );
+    assert_eq!(dest, [0, 0, 0, 3, 2, 1, 0, 0, 0]);
+}
+
+#[test]
+fn try_sub_surface() {
+    let mut data = [0u8, 1, 2, 3, 4, 5];
+    let mut surface = GenericSurface::new(&mut data[..], size(3, 2)).unwrap();
+
+    // exact fit
+    let sub = surface.try_sub_surface(point(1, 0), size(2, 2)).unwrap();
+    assert_eq!(sub.surface_size(), size(2, 2));
+    assert_eq!(sub.surface_get(point(1, 1)), Some(&5));
+    assert!(surface.try_sub_surface(point(0, 0), size(3, 2)).is_some());
+    assert!(surface.try_sub_surface(point(3, 2), size(0, 0)).is_some());
+
+    // one over
+    assert!(surface.try_sub_surface(point(1, 0), size(3, 2)).is_none());
+    assert!(surface.try_sub_surface(point(0, 1), size(3, 2)).is_none());
+    assert!(surface
+        .try_sub_surface(point(u32::MAX, 0), size(2, 1))
+        .is_none());
+
+    surface
+        .try_sub_surface_mut(point(2, 1), size(1, 1))
+        .unwrap()
+        .fill(9);
+    assert!(surface
+        .try_sub_surface_mut(point(2, 1), size(2, 1))
+        .is_none());
+    assert_eq!(data, [0, 1, 2, 3, 4, 9]);
+}
+
+#[test]
+fn rect() {
+    use crate::Rect;
+
+    let a = Rect::new(point(1, 1), size(3, 2));
+    let b = Rect::new(point(2, 0), size(5, 2));
+
+    assert_eq!(a.intersect(&b), Some(Rect::new(point(2, 1), size(2, 1))));
+    assert_eq!(b.intersect(&a), a.intersect(&b));
+    assert_eq!(a.intersect(&a), Some(a));
+    assert_eq!(a.intersect(&Rect::new(point(4, 1), size(1, 1))), None);
+    assert_eq!(a.intersect(&Rect::new(point(2, 2), size(0, 5))), None);
+    assert_eq!(
+        Rect::new(point(u32::MAX - 1, 0), size(u32::MAX, 1))
+            .intersect(&Rect::new(point(0, 0), size(u32::MAX, 1))),
+        Some(Rect::new(point(u32::MAX - 1, 0), size(1, 1)))
+    );
+
+    assert!(a.contains_point(point(1, 1)));
+    assert!(a.contains_point(point(3, 2)));
+    assert!(!a.contains_point(point(4, 2)));
+    assert!(!a.contains_point(point(0, 1)));
+    let empty = Rect::new(point(0, 0), size(0, 3));
+    assert!(!empty.contains_point(point(0, 0)));
+    assert!(empty.is_empty());
+
+    let data = [0u8, 1, 2, 3, 4, 5];
+    let surface = GenericSurface::new(&data[..], size(3, 2)).unwrap();
+    let sub = surface.sub_surface_rect(Rect::new(point(1, 0), size(2, 2)));
+    assert_eq!(sub.surface_get(point(1, 1)), Some(&5));
+    assert_eq!(Rect::from(&sub), Rect::new(point(1, 0), size(2, 2)));
+    assert_eq!(
+        surface.offset_surface(point(2, 1)).rect(),
+        Rect::new(point(2, 1), size(1, 1))
+    );
+}
+
+#[test]
+fn scrolled() {
+    use crate::blit_scrolled;
+
+    #[rustfmt::skip]
+    let data = [
+        1u8, 2, 3,
+        4, 5, 6,
+        7, 8, 9,
+    ];
+    let src = GenericSurface::new(&data[..], size(3, 3)).unwrap();
+    let mut dest = [0u8; 9];
+
+    blit_scrolled(
+        GenericSurface::new(&mut dest[..], size(3, 3)).unwrap(),
+        src,
+        point(1, 1),
+        &[],
+    );
+    #[rustfmt::skip]
+    assert_eq!(dest, [
+        5, 6, 4,
+        8, 9, 7,
+        2, 3, 1,
+    ]);
+
+    // the source repeats to fill a bigger destination
+    let mut dest = [0u8; 10];
+    blit_scrolled(
+        GenericSurface::new(&mut dest[..], size(5, 2)).unwrap(),
+        src,
+        point(5, 0),
+        &[Transform::FlipHorizontal],
+    );
+    assert_eq!(dest, [1, 3, 2, 1, 3, 4, 6, 5, 4, 6]);
+
+    // same as blitting a wrapping source
+    let mut wrapped = [0u8; 10];
+    blit(
+        GenericSurface::new(&mut wrapped[..], size(5, 2)).unwrap(),
+        src.flip_x().tiled(size(9, 3)).offset_surface(point(2, 0)),
+        &[],
+    );
+    assert_eq!(dest, wrapped);
+}
+
+#[test]
+fn index_of() {
+    let data = [0u8; 10];
+    let surface = GenericSurface::with_stride(&data[..], size(3, 2), 5).unwrap();
+
+    assert_eq!(surface.index_of(point(0, 0)), Some(0));
+    assert_eq!(surface.index_of(point(2, 1)), Some(7));
+    assert_eq!(surface.index_of(point(3, 1)), None);
+    assert_eq!(surface.index_of(point(0, 2)), None);
+
+    assert_eq!(surface.offset_of(point(3, 1)), 8);
+    assert_eq!(surface.offset_of(point(1, 4)), 21);
+
+    let compact = GenericSurface::new(&data[..], size(5, 2)).unwrap();
+    for (pt, _) in compact.pixels() {
+        assert_eq!(
+            compact.index_of(pt),
+            Some(pt.y as usize * 5 + pt.x as usize)
+        );
+    }
+}
+
+#[test]
+fn surface_shorthand() {
+    use crate::{surface, surface_mut};
+
+    let src = [1u8, 2, 3, 4, 5, 6];
+    let mut dest = [0u8; 6];
+
+    blit(
+        surface_mut(&mut dest, 2).unwrap(),
+        surface(&src, 3).unwrap(),
+        &[Transform::Transpose],
+    );
+    assert_eq!(dest, [1, 4, 2, 5, 3, 6]);
+
+    assert_eq!(surface(&src, 2).unwrap().surface_size(), size(2, 3));
+    assert!(surface(&src, 4).is_none());
+    assert!(surface_mut(&mut dest, 0).is_none());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn copy_on_write() {
+    use alloc::borrow::Cow;
+
+    let data = [1u8, 2, 3, 4, 0, 5, 6, 0];
+    let mut surface = GenericSurface::with_stride(Cow::Borrowed(&data[..]), size(3, 2), 4).unwrap();
+
+    assert_eq!(surface.surface_get(point(2, 1)), Some(&6));
+    assert!(!surface.is_owned());
+
+    surface.to_mut().fill(7);
+    assert!(surface.is_owned());
+    assert_eq!(surface.surface_get(point(2, 1)), Some(&7));
+    assert_eq!(data, [1, 2, 3, 4, 0, 5, 6, 0]);
+
+    // writes after the first one don't clone again
+    let values = surface.to_mut().as_ptr();
+    *surface.to_mut().surface_get_mut(point(0, 0)).unwrap() = 9;
+    assert_eq!(surface.to_mut().as_ptr(), values);
+    let owned = surface.into_owned();
+    assert_eq!(owned.stride(), 4);
+    assert_eq!(owned.surface_get(point(0, 0)), Some(&9));
+    assert_eq!(owned.surface_get(point(1, 0)), Some(&7));
+}
+
+#[test]
+fn tracked() {
+    use crate::{Rect, TrackedSurface};
+
+    let mut data = [0u8; 25];
+    let mut surface = TrackedSurface::new(GenericSurface::new(&mut data[..], size(5, 5)).unwrap());
+    assert_eq!(surface.dirty(), None);
+
+    blit(
+        surface.offset_surface_mut(point(1, 2)),
+        SingleValueSurface::new(1u8, size(2, 2)),
+        &[],
+    );
+    assert_eq!(surface.dirty(), Some(Rect::new(point(1, 2), size(2, 2))));
+
+    *surface.get_mut(4, 0).unwrap() = 2;
+    assert_eq!(
+        surface.take_dirty(),
+        Some(Rect::new(point(1, 0), size(4, 4)))
+    );
+    assert_eq!(surface.dirty(), None);
+
+    // reads and out of bounds writes aren't tracked
+    assert_eq!(surface.surface_get(point(4, 0)), Some(&2));
+    assert!(surface.surface_get_mut(point(5, 0)).is_none());
+    assert_eq!(surface.dirty(), None);
+
+    let src = [3u8; 10];
+    blit(
+        &mut surface,
+        GenericSurface::new(&src[..], size(5, 2)).unwrap(),
+        &[],
+    );
+    assert_eq!(
+        surface.take_dirty(),
+        Some(Rect::new(point(0, 0), size(5, 2)))
+    );
+
+    // writing the same values still marks them
+    blit(
+        &mut surface,
+        GenericSurface::new(&src[..], size(5, 2)).unwrap(),
+        &[],
+    );
+    assert_eq!(surface.dirty(), Some(Rect::new(point(0, 0), size(5, 2))));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_tracked() {
+    use crate::TrackedSurface;
+
+    let mut surface = TrackedSurface::new(GenericSurface::new_filled(size(2, 1), 0_u8));
+    *surface.get_mut(1, 0).unwrap() = 4;
+    assert!(surface.dirty().is_some());
+
+    // the dirty rectangle isn't part of the data
+    let json = serde_json::to_string(&surface).unwrap();
+    assert_eq!(
+        json,
+        r#"{"surface":{"slice":[0,4],"size":[2,1],"stride":2}}"#
+    );
+
+    let parsed: TrackedSurface<GenericSurface<Vec<u8>, u8>, u8> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.dirty(), None);
+    assert_eq!(parsed.surface_get(point(1, 0)), Some(&4));
+}
+
+#[test]
+fn change_tracked() {
+    use crate::{ChangeTrackedSurface, Rect};
+
+    let mut data = [0u8; 25];
+    let mut surface =
+        ChangeTrackedSurface::new(GenericSurface::new(&mut data[..], size(5, 5)).unwrap());
+
+    // overwriting with identical values isn't a change
+    blit(&mut surface, SingleValueSurface::new(0u8, size(5, 5)), &[]);
+    assert_eq!(surface.dirty(), None);
+
+    #[rustfmt::skip]
+    let src = [
+        0u8, 0, 0,
+        0, 0, 1,
+        0, 2, 0,
+    ];
+    blit(
+        surface.offset_surface_mut(point(1, 1)),
+        GenericSurface::new(&src[..], size(3, 3)).unwrap(),
+        &[],
+    );
+    assert_eq!(surface.dirty(), Some(Rect::new(point(2, 2), size(2, 2))));
+
+    // the last access is included before it's settled
+    *surface.get_mut(0, 4).unwrap() = 3;
+    assert_eq!(
+        surface.take_dirty(),
+        Some(Rect::new(point(0, 2), size(4, 3)))
+    );
+    assert_eq!(surface.dirty(), None);
+
+    // accessing without changing leaves it clean
+    let _ = surface.get_mut(0, 4);
+    assert_eq!(surface.dirty(), None);
+
+    assert!(!surface.set(point(0, 4), 3));
+    assert_eq!(surface.dirty(), None);
+    assert!(surface.set(point(4, 0), 5));
+    assert!(!surface.set(point(5, 0), 5));
+    assert_eq!(
+        surface.take_dirty(),
+        Some(Rect::new(point(4, 0), size(1, 1)))
+    );
+
+    assert_eq!(data[4], 5);
+    assert_eq!(data[20], 3);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_change_tracked() {
+    use crate::ChangeTrackedSurface;
+
+    let mut surface = ChangeTrackedSurface::new(GenericSurface::new_filled(size(2, 1), 0_u8));
+    *surface.get_mut(1, 0).unwrap() = 4;
+
+    // neither the dirty rectangle nor the pending value are part of the data
+    let json = serde_json::to_string(&surface).unwrap();
+    assert_eq!(
+        json,
+        r#"{"surface":{"slice":[0,4],"size":[2,1],"stride":2}}"#
+    );
+
+    let mut parsed: ChangeTrackedSurface<GenericSurface<Vec<u8>, u8>, u8> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.take_dirty(), None);
+    assert_eq!(surface.take_dirty().map(|rect| rect.size), Some(size(1, 1)));
+}
+
+#[test]
+fn clear() {
+    let mut data = [7u8; 6];
+    let mut surface = GenericSurface::new(&mut data[..], size(3, 2)).unwrap();
+
+    surface.sub_surface_mut(point(1, 0), size(2, 1)).clear();
+    assert_eq!(data, [7, 0, 0, 7, 7, 7]);
+
+    let mut options = [[Some(1u8); 2]; 2];
+    options.clear();
+    assert_eq!(options, [[None; 2]; 2]);
+}
+
+#[test]
+fn strided() {
+    use crate::blit_strided;
+
+    let src = core::array::from_fn::<u8, 15, _>(|i| i as u8);
+    let src = GenericSurface::new(&src[..], size(5, 3)).unwrap();
+
+    let mut dest = [0_u8; 8];
+    blit_strided(
+        GenericSurface::new(&mut dest, size(4, 2)).unwrap(),
+        &src,
+        size(2, 2),
+        &[],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        0, 2, 4, 0,
+        10, 12, 14, 0,
+    ];
+
+    assert_eq!(dest, correct);
+
+    let mut dest = [0_u8; 6];
+    blit_strided(
+        GenericSurface::new(&mut dest, size(2, 3)).unwrap(),
+        &src,
+        size(2, 2),
+        &[Transform::Rotate90Cw],
+    );
+
+    #[rustfmt::skip]
+    let correct = [
+        10, 0,
+        12, 2,
+        14, 4,
+    ];
+
+    assert_eq!(dest, correct);
+
+    let mut dest = [7_u8; 4];
+    blit_strided(
+        GenericSurface::new(&mut dest, size(2, 2)).unwrap(),
+        &src,
+        size(0, 1),
+        &[],
+    );
+    assert_eq!(dest, [7; 4]);
+}
+
+#[test]
+fn shift() {
+    use mint::Vector2;
+
+    #[rustfmt::skip]
+    let src = [
+        1_u8, 2, 3,
+        4, 5, 6,
+        7, 8, 9,
+    ];
+
+    let shifted = |x: i32, y: i32| {
+        let mut dest = src;
+        GenericSurface::new(&mut dest, size(3, 3))
+            .unwrap()
+            .shift(Vector2 { x, y }, 0);
+        dest
+    };
+
+    #[rustfmt::skip]
+    assert_eq!(shifted(1, 0), [
+        0, 1, 2,
+        0, 4, 5,
+        0, 7, 8,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(shifted(-2, 0), [
+        3, 0, 0,
+        6, 0, 0,
+        9, 0, 0,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(shifted(0, 1), [
+        0, 0, 0,
+        1, 2, 3,
+        4, 5, 6,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(shifted(0, -1), [
+        4, 5, 6,
+        7, 8, 9,
+        0, 0, 0,
+    ]);
+
+    #[rustfmt::skip]
+    assert_eq!(shifted(-1, 1), [
+        0, 0, 0,
+        2, 3, 0,
+        5, 6, 0,
+    ]);
+
+    assert_eq!(shifted(0, 0), src);
+    assert_eq!(shifted(3, 0), [0; 9]);
+    assert_eq!(shifted(i32::MIN, i32::MAX), [0; 9]);
+
+    // the row-wise `GenericSurface` version matches the value by value one
+    #[rustfmt::skip]
+    let src = [
+        1_u8, 2, 3, 4, 0,
+        5, 6, 7, 8, 0,
+        9, 10, 11, 12, 0,
+        13, 14, 15, 16,
+    ];
+
+    for x in -5..=5 {
+        for y in -5..=5 {
+            let mut fast = src;
+            let mut slow = src;
+
+            GenericSurface::with_stride(&mut fast[..], size(4, 4), 5)
+                .unwrap()
+                .shift(Vector2 { x, y }, 0);
+            GenericSurface::with_stride(&mut slow[..], size(4, 4), 5)
+                .unwrap()
+                .offset_surface_mut(point(0, 0))
+                .shift(Vector2 { x, y }, 0);
+
+            assert_eq!(fast, slow, "delta {x}, {y}");
+        }
+    }
+}
+
+#[cfg(feature = "rgb")]
+#[test]
+fn premultiply() {
+    use crate::{premultiply, unpremultiply};
+    use rgb::RGBA8;
+
+    let mut values = [
+        RGBA8::new(200, 100, 50, 128),
+        RGBA8::new(255, 255, 255, 0),
+        RGBA8::new(10, 20, 30, 255),
+    ];
+    premultiply(GenericSurface::new(&mut values, size(3, 1)).unwrap());
+    assert_eq!(
+        values,
+        [
+            RGBA8::new(100, 50, 25, 128),
+            RGBA8::new(0, 0, 0, 0),
+            RGBA8::new(10, 20, 30, 255),
+        ]
+    );
+
+    // the round trip only loses the precision that the alpha can't hold
+    let mut values = Vec::new();
+    for a in 1..=255_u8 {
+        for c in 0..=255_u8 {
+            values.push(RGBA8::new(c, 255 - c, c / 2, a));
+        }
+    }
+    let original = values.clone();
+
+    let mut surface = GenericSurface::new(&mut values[..], size(256, 255)).unwrap();
+    premultiply(&mut surface);
+    unpremultiply(&mut surface);
+
+    for (value, original) in values.iter().zip(&original) {
+        let alpha = original.a as u32;
+        let close = |c: u8, o: u8| c.abs_diff(o) as u32 * 2 * alpha <= 255 + alpha;
+
+        assert_eq!(value.a, original.a);
+        assert!(close(value.r, original.r), "{value:?} != {original:?}");
+        assert!(close(value.g, original.g), "{value:?} != {original:?}");
+        assert!(close(value.b, original.b), "{value:?} != {original:?}");
+    }
+}
+
+#[test]
+fn surfaces_eq() {
+    use crate::surfaces_eq;
+
+    #[rustfmt::skip]
+    let big = [
+        1_u8, 2, 3,
+        4, 5, 6,
+    ];
+    let small = [2_u8, 5];
+    let big = GenericSurface::new(&big, size(3, 2)).unwrap();
+    let small = GenericSurface::new(&small, size(1, 2)).unwrap();
+
+    assert!(surfaces_eq(big.sub_surface(point(1, 0), size(1, 2)), small));
+    assert!(surfaces_eq(
+        GenericSurface::with_stride(&[2_u8, 0, 5][..], size(1, 2), 2).unwrap(),
+        small
+    ));
+    const COLUMN: [u8; 2] = [2, 5];
+    assert!(surfaces_eq(
+        FnSurface::new(size(1, 2), |pt| &COLUMN[pt.y as usize]),
+        small
+    ));
+
+    assert!(!surfaces_eq(
+        big.sub_surface(point(0, 0), size(1, 2)),
+        small
+    ));
+    assert!(!surfaces_eq(big, small));
+}
+
+#[test]
+fn surface_hash() {
+    use crate::surface_hash;
+    use core::hash::Hasher;
+
+    // records everything fed to it, so equal hashes mean equal input
+    #[derive(Default)]
+    struct Recorder(Vec<u8>);
+
+    impl Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    let hash = |surface: &dyn Fn(&mut Recorder)| {
+        let mut hasher = Recorder::default();
+        surface(&mut hasher);
+        hasher.0
+    };
+
+    let padded = GenericSurface::with_stride(&[1_u8, 2, 0, 3, 4][..], size(2, 2), 3).unwrap();
+    let packed = GenericSurface::new(&[1_u8, 2, 3, 4][..], size(2, 2)).unwrap();
+    let wide = GenericSurface::new(&[1_u8, 2, 3, 4][..], size(4, 1)).unwrap();
+
+    let packed_hash = hash(&|hasher| surface_hash(packed, hasher));
+    assert_eq!(hash(&|hasher| surface_hash(padded, hasher)), packed_hash);
+    assert_eq!(
+        hash(&|hasher| surface_hash(packed.flip_x().flip_x(), hasher)),
+        packed_hash
+    );
+    assert_ne!(hash(&|hasher| surface_hash(wide, hasher)), packed_hash);
+    assert_ne!(
+        hash(&|hasher| surface_hash(packed.flip_x(), hasher)),
+        packed_hash
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn surface_hash_u64() {
+    use crate::surface_hash_u64;
+
+    let padded = GenericSurface::with_stride(&[1_u8, 2, 0, 3, 4][..], size(2, 2), 3).unwrap();
+    let packed = GenericSurface::new(&[1_u8, 2, 3, 4][..], size(2, 2)).unwrap();
+
+    assert_eq!(surface_hash_u64(padded), surface_hash_u64(packed));
+    assert_ne!(surface_hash_u64(packed.flip_y()), surface_hash_u64(packed));
+}
+
+#[test]
+fn transformed_coords() {
+    use crate::transformed_coords;
+
+    #[rustfmt::skip]
+    let coords = [
+        (point(0, 0), point(0, 1)), (point(1, 0), point(0, 0)),
+        (point(0, 1), point(1, 1)), (point(1, 1), point(1, 0)),
+        (point(0, 2), point(2, 1)), (point(1, 2), point(2, 0)),
+    ];
+    assert!(transformed_coords(size(3, 2), &[Transform::Rotate90Cw]).eq(coords));
+
+    let src = [0_u8; 12];
+    let src = GenericSurface::new(&src[..], size(4, 3)).unwrap();
+
+    for transforms in [
+        &[][..],
+        &[Transform::FlipBoth, Transform::UpScale { x: 2, y: 3 }],
+        &[Transform::Transpose, Transform::Rotate90Ccw],
+        &[
+            Transform::UpScale { x: 3, y: 2 },
+            Transform::Rotate90Cw,
+            Transform::DownScale { x: 2, y: 2 },
+        ],
+    ] {
+        let dest_size = Transform::transform_size_chain(transforms, src.surface_size());
+        let mut dest = alloc::vec![0_u8; (dest_size.x * dest_size.y) as usize];
+
+        let mut visited = Vec::new();
+        blit_with_positions(
+            GenericSurface::new(&mut dest[..], dest_size).unwrap(),
+            src,
+            transforms,
+            |_, _, dest_pos, src_pos| visited.push((dest_pos, src_pos)),
+        );
+
+        let coords = transformed_coords(src.surface_size(), transforms);
+        assert_eq!(coords.size_hint(), (visited.len(), Some(visited.len())));
+        assert_eq!(coords.collect::<Vec<_>>(), visited);
+    }
+}
+
+#[test]
+fn blit_from() {
+    let src = [1_u8, 2, 3, 4];
+    let src = GenericSurface::new(&src[..], size(2, 2)).unwrap();
+
+    let mut dest = [0_u8; 9];
+    let mut surface = GenericSurface::new(&mut dest[..], size(3, 3)).unwrap();
+
+    surface
+        .sub_surface_mut(point(1, 1), size(2, 2))
+        .blit_from(src, &[Transform::FlipHorizontal]);
+    surface.blit_from_masked(src, &[], &1);
+    surface
+        .offset_surface_mut(point(2, 0))
+        .blit_from_with(src, &[], |dest, src, _| *dest += src * 10);
+
+    #[rustfmt::skip]
+    let correct = [
+        0, 2, 10,
+        3, 4, 31,
+        0, 4, 3,
+    ];
+
+    assert_eq!(dest, correct);
+}
+
+#[test]
+fn split_at_row() {
+    #[rustfmt::skip]
+    let mut values = [
+        1_u8, 2, 0,
+        3, 4, 0,
+        5, 6,
+    ];
+    let mut surface = GenericSurface::with_stride(&mut values[..], size(2, 3), 3).unwrap();
+
+    let (top, mut bottom) = surface.split_at_row(1);
+    assert_eq!(top.surface_size(), size(2, 1));
+    assert_eq!(bottom.surface_size(), size(2, 2));
+    blit(&mut bottom, &top, &[Transform::FlipHorizontal]);
+
+    #[rustfmt::skip]
+    assert_eq!(values, [
+        1, 2, 0,
+        2, 1, 0,
+        5, 6,
+    ]);
+
+    let mut surface = GenericSurface::with_stride(&mut values[..], size(2, 3), 3).unwrap();
+    let (top, bottom) = surface.split_at_row(3);
+    assert_eq!(top.surface_size(), size(2, 3));
+    assert_eq!(top.surface_get(point(1, 2)), Some(&6));
+    assert_eq!(bottom.surface_size(), size(2, 0));
+}
+
+#[test]
+#[should_panic(expected = "split row out of bounds")]
+fn split_at_row_out_of_bounds() {
+    let mut values = [0_u8; 4];
+    GenericSurface::new(&mut values[..], size(2, 2))
+        .unwrap()
+        .split_at_row(3);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn blit_par() {
+    use crate::blit_par;
+
+    let src = core::array::from_fn::<u16, { 37 * 23 }, _>(|i| i as u16);
+    let src = GenericSurface::new(&src[..], size(37, 23)).unwrap();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+
+    for transforms in [
+        &[][..],
+        &[Transform::FlipBoth, Transform::FlipBoth],
+        &[Transform::Rotate90Cw],
+        &[Transform::UpScale { x: 2, y: 3 }, Transform::Transpose],
+        &[Transform::FlipVertical, Transform::DownScale { x: 3, y: 2 }],
+    ] {
+        for dest_size in [size(40, 70), size(20, 10), size(23, 37)] {
+            let len = (dest_size.x * dest_size.y) as usize;
+
+            let mut serial = alloc::vec![0_u16; len];
+            blit(
+                GenericSurface::new(&mut serial[..], dest_size).unwrap(),
+                src,
+                transforms,
+            );
+
+            let mut parallel = alloc::vec![0_u16; len];
+            pool.install(|| {
+                blit_par(
+                    &mut GenericSurface::new(&mut parallel[..], dest_size).unwrap(),
+                    src,
+                    transforms,
+                )
+            });
+
+            assert_eq!(parallel, serial, "{transforms:?} to {dest_size:?}");
+        }
+    }
+}
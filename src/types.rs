@@ -1,12 +1,21 @@
+use crate::{blit, blit_masked, blit_with, funcs::reversed, Transform};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, vec::Vec};
 use core::{
+    fmt,
     marker::PhantomData,
-    ops::{Deref, DerefMut, Index, IndexMut},
+    ops::{
+        Bound, Deref, DerefMut, Index, IndexMut, Range, RangeFrom, RangeFull, RangeInclusive,
+        RangeTo, RangeToInclusive,
+    },
 };
 
 /// Point type.
 pub type Point = mint::Point2<u32>;
 /// Size type.
 pub type Size = mint::Vector2<u32>;
+/// Signed point type, for positions that may be outside of a surface.
+pub type SignedPoint = mint::Point2<i32>;
 
 /// Quickly construct a `Point`.
 #[inline]
@@ -20,6 +29,111 @@ pub const fn size(x: u32, y: u32) -> Size {
     Size { x, y }
 }
 
+/// Quickly construct a `SignedPoint`.
+#[inline]
+pub const fn signed_point(x: i32, y: i32) -> SignedPoint {
+    SignedPoint { x, y }
+}
+
+/// Quickly construct a `GenericSurface` over a slice, inferring the height from `width`.
+///
+/// Returns `None` if `width == 0` or if the slice length is not a multiple of `width` (see [`GenericSurface::new_infer`]).
+#[inline]
+pub fn surface<T>(slice: &[T], width: u32) -> Option<GenericSurface<&[T], T>> {
+    GenericSurface::new_infer(slice, width)
+}
+
+/// Quickly construct a mutable `GenericSurface` over a slice, inferring the height from `width`.
+///
+/// Returns `None` if `width == 0` or if the slice length is not a multiple of `width` (see [`GenericSurface::new_infer`]).
+#[inline]
+pub fn surface_mut<T>(slice: &mut [T], width: u32) -> Option<GenericSurface<&mut [T], T>> {
+    GenericSurface::new_infer(slice, width)
+}
+
+/// Number of values in a surface of size `size`.
+#[inline]
+pub const fn area(size: Size) -> u64 {
+    size.x as u64 * size.y as u64
+}
+
+/// Checks if `pt` is inside a surface of size `size`.
+#[inline]
+pub const fn size_contains(size: Size, pt: Point) -> bool {
+    pt.x < size.x && pt.y < size.y
+}
+
+/// Moves `pt` to the closest point inside a surface of size `size`.
+///
+/// If the size is zero in some dimension, that coordinate becomes 0 (which is still outside the surface).
+#[inline]
+pub const fn clamp_point(pt: Point, size: Size) -> Point {
+    let max_x = size.x.saturating_sub(1);
+    let max_y = size.y.saturating_sub(1);
+
+    point(
+        if pt.x < max_x { pt.x } else { max_x },
+        if pt.y < max_y { pt.y } else { max_y },
+    )
+}
+
+/// Rectangle with the top-left corner at `origin`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    /// Position of the top-left corner.
+    pub origin: Point,
+    /// Rectangle size.
+    pub size: Size,
+}
+
+impl Rect {
+    /// Construct a new rectangle.
+    #[inline]
+    pub const fn new(origin: Point, size: Size) -> Self {
+        Self { origin, size }
+    }
+
+    /// Checks whether the rectangle has no area.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.size.x == 0 || self.size.y == 0
+    }
+
+    /// Checks whether `pt` is inside this rectangle.
+    #[inline]
+    pub const fn contains_point(&self, pt: Point) -> bool {
+        pt.x >= self.origin.x
+            && pt.y >= self.origin.y
+            && size_contains(self.size, point(pt.x - self.origin.x, pt.y - self.origin.y))
+    }
+
+    /// Returns the overlapping part of the two rectangles, `None` if they don't overlap.
+    #[inline]
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        // the ends are computed in `u64` so they can't overflow
+        let range = |a_start: u32, a_len: u32, b_start: u32, b_len: u32| {
+            let start = a_start.max(b_start);
+            let end = (a_start as u64 + a_len as u64).min(b_start as u64 + b_len as u64);
+
+            (end > start as u64).then(|| (start, (end - start as u64) as u32))
+        };
+
+        let (x, width) = range(self.origin.x, self.size.x, other.origin.x, other.size.x)?;
+        let (y, height) = range(self.origin.y, self.size.y, other.origin.y, other.size.y)?;
+
+        Some(Rect::new(point(x, y), size(width, height)))
+    }
+}
+
+impl<S, Item> From<&SubSurface<S, Item>> for Rect {
+    /// The rectangular part of the original surface used by the sub-surface.
+    #[inline]
+    fn from(surface: &SubSurface<S, Item>) -> Self {
+        surface.rect()
+    }
+}
+
 /// 2D immutable surface trait.
 pub trait Surface<T> {
     /// Surface size.
@@ -28,7 +142,30 @@ pub trait Surface<T> {
     /// Get a value at (pt.x, pt.y).
     fn surface_get(&self, pt: Point) -> Option<&T>;
 
+    /// Get the row at `y` as a slice of `surface_size().x` values, if the surface stores it contiguously.
+    ///
+    /// Used by [`blit`](crate::blit) to copy whole rows at once. The default implementation returns `None`.
+    #[inline]
+    fn surface_row(&self, _y: u32) -> Option<&[T]> {
+        None
+    }
+
+    /// Get a value at (x, y).
+    #[inline]
+    fn get(&self, x: u32, y: u32) -> Option<&T> {
+        self.surface_get(point(x, y))
+    }
+
+    /// Checks whether (pt.x, pt.y) is inside this surface.
+    #[inline]
+    fn contains(&self, pt: Point) -> bool {
+        size_contains(self.surface_size(), pt)
+    }
+
     /// Create a [`SubSurface`] that only uses a rectangular part of this surface.
+    ///
+    /// The rectangle is silently clamped to this surface, so the sub-surface may be smaller than `size`.
+    /// Use [`Surface::try_sub_surface`] to catch rectangles that don't fit.
     #[inline]
     fn sub_surface(&self, offset: Point, size: Size) -> SubSurface<&Self, T>
     where
@@ -37,7 +174,31 @@ pub trait Surface<T> {
         SubSurface::new(self, offset, size)
     }
 
+    /// Create a [`SubSurface`] that only uses the part of this surface inside `rect`.
+    ///
+    /// Same as [`Surface::sub_surface`] with the rectangle origin and size.
+    #[inline]
+    fn sub_surface_rect(&self, rect: Rect) -> SubSurface<&Self, T>
+    where
+        Self: Sized,
+    {
+        SubSurface::new(self, rect.origin, rect.size)
+    }
+
+    /// Create a [`SubSurface`] that only uses a rectangular part of this surface.
+    ///
+    /// Returns `None` if the rectangle extends past this surface.
+    #[inline]
+    fn try_sub_surface(&self, offset: Point, size: Size) -> Option<SubSurface<&Self, T>>
+    where
+        Self: Sized,
+    {
+        rect_fits(offset, size, self.surface_size()).then(|| SubSurface::new(self, offset, size))
+    }
+
     /// Create a [`SubSurface`] that only uses a rectangular part of this surface starting from (offset.x, offset.y).
+    ///
+    /// The sub-surface is empty if the offset is outside of this surface.
     #[inline]
     fn offset_surface(&self, offset: Point) -> SubSurface<&Self, T>
     where
@@ -47,11 +208,17 @@ pub trait Surface<T> {
         SubSurface::new(
             self,
             offset,
-            size(sur_size.x - offset.x, sur_size.y - offset.y),
+            size(
+                sur_size.x.saturating_sub(offset.x),
+                sur_size.y.saturating_sub(offset.y),
+            ),
         )
     }
 
     /// Create a [`SubSurface`] that only uses a rectangular part of this surface.
+    ///
+    /// The rectangle is silently clamped to this surface, so the sub-surface may be smaller than `size`.
+    /// Use [`Surface::try_sub_surface_mut`] to catch rectangles that don't fit.
     #[inline]
     fn sub_surface_mut(&mut self, offset: Point, size: Size) -> SubSurface<&mut Self, T>
     where
@@ -60,7 +227,31 @@ pub trait Surface<T> {
         SubSurface::new(self, offset, size)
     }
 
+    /// Create a [`SubSurface`] that only uses the part of this surface inside `rect`.
+    ///
+    /// Same as [`Surface::sub_surface_mut`] with the rectangle origin and size.
+    #[inline]
+    fn sub_surface_rect_mut(&mut self, rect: Rect) -> SubSurface<&mut Self, T>
+    where
+        Self: Sized,
+    {
+        SubSurface::new(self, rect.origin, rect.size)
+    }
+
+    /// Create a [`SubSurface`] that only uses a rectangular part of this surface.
+    ///
+    /// Returns `None` if the rectangle extends past this surface.
+    #[inline]
+    fn try_sub_surface_mut(&mut self, offset: Point, size: Size) -> Option<SubSurface<&mut Self, T>>
+    where
+        Self: Sized,
+    {
+        rect_fits(offset, size, self.surface_size()).then(|| SubSurface::new(self, offset, size))
+    }
+
     /// Create a [`SubSurface`] that only uses a rectangular part of this surface starting from (offset.x, offset.y).
+    ///
+    /// The sub-surface is empty if the offset is outside of this surface.
     #[inline]
     fn offset_surface_mut(&mut self, offset: Point) -> SubSurface<&mut Self, T>
     where
@@ -70,7 +261,10 @@ pub trait Surface<T> {
         SubSurface::new(
             self,
             offset,
-            size(sur_size.x - offset.x, sur_size.y - offset.y),
+            size(
+                sur_size.x.saturating_sub(offset.x),
+                sur_size.y.saturating_sub(offset.y),
+            ),
         )
     }
 
@@ -84,6 +278,8 @@ pub trait Surface<T> {
     }
 
     /// Create a [`SubSurface`] that only uses a rectangular part of this surface starting from (offset.x, offset.y).
+    ///
+    /// The sub-surface is empty if the offset is outside of this surface.
     #[inline]
     fn into_offset_surface(self, offset: Point) -> SubSurface<Self, T>
     where
@@ -93,9 +289,133 @@ pub trait Surface<T> {
         SubSurface::new(
             self,
             offset,
-            size(sur_size.x - offset.x, sur_size.y - offset.y),
+            size(
+                sur_size.x.saturating_sub(offset.x),
+                sur_size.y.saturating_sub(offset.y),
+            ),
         )
     }
+
+    /// Returns an iterator over the positions and values of this surface in row-major order.
+    #[inline]
+    fn pixels(&self) -> SurfacePixels<'_, Self, T>
+    where
+        Self: Sized,
+    {
+        SurfacePixels {
+            surface: self,
+            pos: point(0, 0),
+            ghost: PhantomData,
+        }
+    }
+
+    /// Fold every value of this surface (with its position) into an accumulator, in row-major order.
+    #[inline]
+    fn fold_pixels<A>(&self, init: A, mut func: impl FnMut(A, Point, &T) -> A) -> A {
+        let size = self.surface_size();
+        let mut acc = init;
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                if let Some(value) = self.surface_get(point(x, y)) {
+                    acc = (func)(acc, point(x, y), value);
+                }
+            }
+        }
+
+        acc
+    }
+
+    /// Count the values of this surface for which `predicate` returns `true`.
+    #[inline]
+    fn count_where(&self, mut predicate: impl FnMut(&T) -> bool) -> usize {
+        self.fold_pixels(0, |count, _, value| count + (predicate)(value) as usize)
+    }
+
+    /// Create a [`FlipSurface`] that views this surface flipped horizontally.
+    #[inline]
+    fn flip_x(self) -> FlipSurface<Self, T>
+    where
+        Self: Sized,
+    {
+        FlipSurface::new(self, true, false)
+    }
+
+    /// Create a [`FlipSurface`] that views this surface flipped vertically.
+    #[inline]
+    fn flip_y(self) -> FlipSurface<Self, T>
+    where
+        Self: Sized,
+    {
+        FlipSurface::new(self, false, true)
+    }
+
+    /// Create a [`TransposeSurface`] that views this surface with x and y swapped.
+    #[inline]
+    fn transpose(self) -> TransposeSurface<Self, T>
+    where
+        Self: Sized,
+    {
+        TransposeSurface::new(self)
+    }
+
+    /// Create a [`TileSurface`] of size `size` that repeats this surface.
+    #[inline]
+    fn tiled(self, size: Size) -> TileSurface<Self, T>
+    where
+        Self: Sized,
+    {
+        TileSurface::new(self, size)
+    }
+
+    /// Create an [`EdgeClampSurface`] that clamps out-of-bounds positions to the edges of this surface.
+    #[inline]
+    fn edge_clamped(self) -> EdgeClampSurface<Self, T>
+    where
+        Self: Sized,
+    {
+        EdgeClampSurface::new(self)
+    }
+
+    /// Create a [`MirrorSurface`] that reflects out-of-bounds positions back into this surface.
+    #[inline]
+    fn mirrored(self) -> MirrorSurface<Self, T>
+    where
+        Self: Sized,
+    {
+        MirrorSurface::new(self)
+    }
+
+    /// Create a [`WrapSurface`] that wraps out-of-bounds positions around this surface.
+    #[inline]
+    fn wrapping(self) -> WrapSurface<Self, T>
+    where
+        Self: Sized,
+    {
+        WrapSurface::new(self)
+    }
+
+    /// Create a [`ZipSurface`] that views this surface and `other` together.
+    ///
+    /// Returns `None` if the surface sizes are not equal.
+    #[inline]
+    fn zip<B, U>(self, other: B) -> Option<ZipSurface<Self, B, T, U>>
+    where
+        Self: Sized,
+        B: Surface<U>,
+    {
+        ZipSurface::new(self, other)
+    }
+
+    /// Create a [`MapSurface`] that views the values of this surface through `func`.
+    #[inline]
+    fn map<'a, U, F>(self, func: F) -> MapSurface<'a, Self, F, T, U>
+    where
+        Self: Sized,
+        F: Fn(&T) -> &'a U,
+    {
+        MapSurface::new(self, func)
+    }
 }
 
 impl<S, T> Surface<T> for &S
@@ -103,69 +423,2435 @@ where
     S: Surface<T>,
 {
     #[inline]
-    fn surface_size(&self) -> Size {
-        (**self).surface_size()
+    fn surface_size(&self) -> Size {
+        (**self).surface_size()
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        (**self).surface_get(pt)
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[T]> {
+        (**self).surface_row(y)
+    }
+}
+
+impl<S, T> Surface<T> for &mut S
+where
+    S: Surface<T>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        (**self).surface_size()
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        (**self).surface_get(pt)
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[T]> {
+        (**self).surface_row(y)
+    }
+}
+
+/// Iterator over the positions and values of a surface, returned by [`Surface::pixels`].
+#[derive(Debug)]
+pub struct SurfacePixels<'a, S, T> {
+    surface: &'a S,
+    pos: Point,
+    ghost: PhantomData<&'a T>,
+}
+
+impl<'a, S, T> Iterator for SurfacePixels<'a, S, T>
+where
+    S: Surface<T>,
+{
+    type Item = (Point, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = self.surface.surface_size();
+
+        while self.pos.y < size.y {
+            let pos = self.pos;
+
+            self.pos.x += 1;
+            if self.pos.x >= size.x {
+                self.pos = point(0, pos.y + 1);
+            }
+
+            if let Some(value) = self.surface.surface_get(pos) {
+                return Some((pos, value));
+            }
+        }
+
+        None
+    }
+}
+
+/// 2D mutable surface trait.
+pub trait SurfaceMut<T>: Surface<T> {
+    /// Get a mutable value at (pt.x, pt.y).
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut T>;
+
+    /// Get the row at `y` as a mutable slice of `surface_size().x` values, if the surface stores it contiguously.
+    ///
+    /// The default implementation returns `None`.
+    #[inline]
+    fn surface_row_mut(&mut self, _y: u32) -> Option<&mut [T]> {
+        None
+    }
+
+    /// Get a mutable value at (x, y).
+    #[inline]
+    fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut T> {
+        self.surface_get_mut(point(x, y))
+    }
+
+    /// Swap the values at `a` and `b`.
+    ///
+    /// Returns `false` (and does nothing) if either point is out of bounds.
+    /// [`GenericSurface`] provides a version of this method that doesn't require `T: Clone`.
+    #[inline]
+    fn swap(&mut self, a: Point, b: Point) -> bool
+    where
+        T: Clone,
+    {
+        let a_value = match self.surface_get(a) {
+            Some(value) if self.surface_get(b).is_some() => value.clone(),
+            _ => return false,
+        };
+
+        if let Some(b_value) = self.surface_get_mut(b) {
+            let b_value = core::mem::replace(b_value, a_value);
+
+            if let Some(a_value) = self.surface_get_mut(a) {
+                *a_value = b_value;
+            }
+        }
+
+        true
+    }
+
+    /// Set every value of this surface to `value`.
+    #[inline]
+    fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.fill_with(|_| value.clone());
+    }
+
+    /// Set every value of this surface to `T::default()`.
+    #[inline]
+    fn clear(&mut self)
+    where
+        T: Default,
+    {
+        self.fill_with(|_| T::default());
+    }
+
+    /// Move every value of this surface by `delta` (positive values move them right and down),
+    /// setting the values in the exposed region to `fill`.
+    ///
+    /// The values moved past the edges are dropped, and the whole surface is filled if `delta` is as big as it.
+    /// The values are copied in the order that reads each one before it's overwritten, like [`GenericSurface::copy_within`],
+    /// which can be called directly to move only part of a [`GenericSurface`] of `Copy` values.
+    /// [`GenericSurface`] overrides this method to move whole rows with slice operations instead.
+    fn shift(&mut self, delta: mint::Vector2<i32>, fill: T)
+    where
+        T: Clone,
+    {
+        let surface_size = self.surface_size();
+        let distance = size(
+            delta.x.unsigned_abs().min(surface_size.x),
+            delta.y.unsigned_abs().min(surface_size.y),
+        );
+        let kept = size(surface_size.x - distance.x, surface_size.y - distance.y);
+        let src_offset = point(
+            if delta.x < 0 { distance.x } else { 0 },
+            if delta.y < 0 { distance.y } else { 0 },
+        );
+        let dest_offset = point(
+            if delta.x > 0 { distance.x } else { 0 },
+            if delta.y > 0 { distance.y } else { 0 },
+        );
+
+        for row in 0..kept.y {
+            let y = if delta.y > 0 { kept.y - 1 - row } else { row };
+
+            for column in 0..kept.x {
+                let x = if delta.x > 0 {
+                    kept.x - 1 - column
+                } else {
+                    column
+                };
+
+                let value = match self.surface_get(point(src_offset.x + x, src_offset.y + y)) {
+                    Some(value) => value.clone(),
+                    None => continue,
+                };
+
+                if let Some(dest) =
+                    self.surface_get_mut(point(dest_offset.x + x, dest_offset.y + y))
+                {
+                    *dest = value;
+                }
+            }
+        }
+
+        let exposed_x = if delta.x > 0 { 0 } else { kept.x };
+        let exposed_y = if delta.y > 0 { 0 } else { kept.y };
+
+        fill_area(
+            self,
+            point(exposed_x, 0),
+            size(distance.x, surface_size.y),
+            &fill,
+        );
+        fill_area(
+            self,
+            point(0, exposed_y),
+            size(surface_size.x, distance.y),
+            &fill,
+        );
+    }
+
+    /// Set every value of this surface to the result of calling `func` with its position.
+    #[inline]
+    fn fill_with(&mut self, mut func: impl FnMut(Point) -> T) {
+        let size = self.surface_size();
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                if let Some(value) = self.surface_get_mut(point(x, y)) {
+                    *value = (func)(point(x, y));
+                }
+            }
+        }
+    }
+
+    /// Blit part of `src` to this surface, cloning the values.
+    ///
+    /// Same as [`blit`]`(self, src, transforms)`, which is more convenient in chains like
+    /// `dest.sub_surface_mut(offset, size).blit_from(src, &[])`.
+    #[inline]
+    fn blit_from(&mut self, src: impl Surface<T>, transforms: &[Transform])
+    where
+        T: Clone,
+        Self: Sized,
+    {
+        blit(self, src, transforms);
+    }
+
+    /// Blit part of `src` to this surface, calling `func` for each pair of values.
+    ///
+    /// Same as [`blit_with`]`(self, src, transforms, func)`.
+    #[inline]
+    fn blit_from_with<S>(
+        &mut self,
+        src: impl Surface<S>,
+        transforms: &[Transform],
+        func: impl FnMut(&mut T, &S, Point),
+    ) where
+        Self: Sized,
+    {
+        blit_with(self, src, transforms, func);
+    }
+
+    /// Blit part of `src` to this surface, ignoring the `mask` values.
+    ///
+    /// Same as [`blit_masked`]`(self, src, transforms, mask)`.
+    #[inline]
+    fn blit_from_masked(&mut self, src: impl Surface<T>, transforms: &[Transform], mask: &T)
+    where
+        T: Clone + PartialEq,
+        Self: Sized,
+    {
+        blit_masked(self, src, transforms, mask);
+    }
+}
+
+/// Set the values in the rectangle (which must be inside the surface) to `value`.
+#[inline]
+fn fill_area<T: Clone, S: SurfaceMut<T> + ?Sized>(
+    surface: &mut S,
+    offset: Point,
+    size: Size,
+    value: &T,
+) {
+    for y in offset.y..offset.y + size.y {
+        for x in offset.x..offset.x + size.x {
+            if let Some(dest) = surface.surface_get_mut(point(x, y)) {
+                dest.clone_from(value);
+            }
+        }
+    }
+}
+
+impl<S, T> SurfaceMut<T> for &mut S
+where
+    S: SurfaceMut<T>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        (*self).surface_get_mut(pt)
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [T]> {
+        (*self).surface_row_mut(y)
+    }
+
+    #[inline]
+    fn shift(&mut self, delta: mint::Vector2<i32>, fill: T)
+    where
+        T: Clone,
+    {
+        (*self).shift(delta, fill);
+    }
+}
+
+/// Reason why a [`GenericSurface`] couldn't be constructed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SurfaceError {
+    /// The slice length doesn't match the one required by the size.
+    LengthMismatch {
+        /// Required slice length.
+        expected: usize,
+        /// Actual slice length.
+        got: usize,
+    },
+    /// The slice length required by the size doesn't fit in `usize`
+    /// (or the inferred height doesn't fit in `u32`).
+    Overflow,
+    /// The width is zero, so the height can't be inferred.
+    ZeroWidth,
+}
+
+impl fmt::Display for SurfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { expected, got } => {
+                write!(f, "surface slice length is {got}, expected {expected}")
+            }
+            Self::Overflow => f.write_str("surface size overflows"),
+            Self::ZeroWidth => f.write_str("surface width is zero"),
+        }
+    }
+}
+
+impl core::error::Error for SurfaceError {}
+
+/// Generic surface with width and height.
+///
+/// With the `serde` feature, it's serialized as its slice, size and stride.
+/// Deserializing fails unless the slice holds exactly the values needed for the size and stride,
+/// `(height - 1) * stride + width` (so the last row can't be padded); the stride defaults to the width.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenericSurface<Slice, Item> {
+    slice: Slice,
+    size: Size,
+    stride: u32,
+    ghost: PhantomData<Item>,
+}
+
+impl<Slice, Item> GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]>,
+{
+    /// Construct a new surface.
+    ///
+    /// Returns `None` if `slice.len() != size.x * size.y` or if `size.x * size.y` overflows.
+    /// Use [`GenericSurface::try_new`] to know which one it was.
+    #[inline]
+    pub fn new(slice: Slice, size: Size) -> Option<Self> {
+        Self::try_new(slice, size).ok()
+    }
+
+    /// Construct a new surface.
+    ///
+    /// Returns [`SurfaceError::Overflow`] if `size.x * size.y` overflows
+    /// or [`SurfaceError::LengthMismatch`] if `slice.len() != size.x * size.y`.
+    #[inline]
+    pub fn try_new(slice: Slice, size: Size) -> Result<Self, SurfaceError> {
+        let expected = (size.x as usize)
+            .checked_mul(size.y as usize)
+            .ok_or(SurfaceError::Overflow)?;
+        let got = slice.as_ref().len();
+
+        if got == expected {
+            Ok(Self {
+                slice,
+                size,
+                stride: size.x,
+                ghost: PhantomData,
+            })
+        } else {
+            Err(SurfaceError::LengthMismatch { expected, got })
+        }
+    }
+
+    /// Constructs a new surface.
+    ///
+    /// Infers the height from slice length and width.
+    /// Returns `None` if `width == 0` or if the slice length is not a multiple of `width`.
+    /// Use [`GenericSurface::try_new_infer`] to know which one it was.
+    #[inline]
+    pub fn new_infer(slice: Slice, width: u32) -> Option<Self> {
+        Self::try_new_infer(slice, width).ok()
+    }
+
+    /// Constructs a new surface.
+    ///
+    /// Infers the height from slice length and width.
+    /// Returns [`SurfaceError::ZeroWidth`] if `width == 0`,
+    /// [`SurfaceError::LengthMismatch`] (expecting the next multiple of `width`) if the slice length is not a multiple of `width`
+    /// or [`SurfaceError::Overflow`] if the height doesn't fit in `u32`.
+    #[inline]
+    pub fn try_new_infer(slice: Slice, width: u32) -> Result<Self, SurfaceError> {
+        let len = slice.as_ref().len();
+
+        if width == 0 {
+            return Err(SurfaceError::ZeroWidth);
+        }
+
+        let height = len.div_ceil(width as usize);
+
+        if len % width as usize != 0 {
+            return Err(SurfaceError::LengthMismatch {
+                expected: height
+                    .checked_mul(width as usize)
+                    .ok_or(SurfaceError::Overflow)?,
+                got: len,
+            });
+        }
+
+        Ok(Self {
+            size: size(
+                width,
+                u32::try_from(height).map_err(|_| SurfaceError::Overflow)?,
+            ),
+            slice,
+            stride: width,
+            ghost: PhantomData,
+        })
+    }
+
+    /// Construct a new surface with padded rows.
+    ///
+    /// Each row starts `stride` values after the previous one, only the first `size.x` values of it are used.
+    /// Returns `None` if `stride < size.x` or if the slice is too short to hold all the rows
+    /// (the last row doesn't have to be padded).
+    #[inline]
+    pub fn with_stride(slice: Slice, size: Size, stride: u32) -> Option<Self> {
+        let min_len = match size.y.checked_sub(1) {
+            Some(last_row) => (last_row as usize)
+                .checked_mul(stride as usize)
+                .and_then(|len| len.checked_add(size.x as usize)),
+            None => Some(0),
+        };
+
+        if stride >= size.x && min_len.is_some_and(|min_len| slice.as_ref().len() >= min_len) {
+            Some(Self {
+                slice,
+                size,
+                stride,
+                ghost: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the rows of this surface, each `size.x` values long.
+    ///
+    /// The row padding (if the surface has a stride) is not included.
+    #[inline]
+    pub fn rows(&self) -> impl Iterator<Item = &[Item]> {
+        let (width, stride) = (self.size.x as usize, self.stride as usize);
+        let mut rest = self.slice.as_ref();
+
+        // the last row may be shorter than the stride
+        (0..self.size.y).map(move |_| {
+            let (row, tail) = rest.split_at(stride.min(rest.len()));
+            rest = tail;
+            &row[..width]
+        })
+    }
+
+    /// Returns the tile at column `col` and row `row` of a grid of `tile_size` tiles (like a sprite sheet).
+    ///
+    /// Returns `None` if the tile doesn't fit inside the surface completely or if the tile size is zero.
+    #[inline]
+    pub fn tile(&self, col: u32, row: u32, tile_size: Size) -> Option<SubSurface<&Self, Item>> {
+        let offset = point(col.checked_mul(tile_size.x)?, row.checked_mul(tile_size.y)?);
+
+        (tile_size.x != 0 && tile_size.y != 0 && rect_fits(offset, tile_size, self.size))
+            .then(|| self.sub_surface(offset, tile_size))
+    }
+
+    /// Returns an iterator over the tiles of a grid of `tile_size` tiles (like a sprite sheet) in row-major order.
+    ///
+    /// The partial tiles at the right and bottom edges (if the size isn't a multiple of `tile_size`) are skipped.
+    /// Nothing is returned if the tile size is zero.
+    #[inline]
+    pub fn tiles(&self, tile_size: Size) -> impl Iterator<Item = SubSurface<&Self, Item>> {
+        let (cols, rows) = if tile_size.x != 0 && tile_size.y != 0 {
+            (self.size.x / tile_size.x, self.size.y / tile_size.y)
+        } else {
+            (0, 0)
+        };
+
+        (0..rows).flat_map(move |row| {
+            (0..cols).map(move |col| {
+                self.sub_surface(point(col * tile_size.x, row * tile_size.y), tile_size)
+            })
+        })
+    }
+
+    /// Distance between the starts of two consecutive rows.
+    ///
+    /// Equals the surface width unless the surface was created with [`GenericSurface::with_stride`].
+    #[inline]
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    /// Index of the value at `pt` in the underlying slice, `None` if `pt` is out of bounds.
+    ///
+    /// Takes the stride into account, so the slice can be processed directly and indexed with it.
+    #[inline]
+    pub fn index_of(&self, pt: Point) -> Option<usize> {
+        size_contains(self.size, pt).then(|| self.offset_of(pt))
+    }
+
+    /// Index of the value at `pt` in the underlying slice, without checking the bounds.
+    ///
+    /// Points past the right edge (or in the padding) give indices of values in the next rows,
+    /// and points below the surface give indices past the end of the slice.
+    #[inline]
+    pub fn offset_of(&self, pt: Point) -> usize {
+        pt.y as usize * self.stride as usize + pt.x as usize
+    }
+
+    /// Returns the slice used to create the surface.
+    #[inline]
+    pub fn into_inner(self) -> Slice {
+        self.slice
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Slice, Item> serde::Serialize for GenericSurface<Slice, Item>
+where
+    Slice: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        #[serde(rename = "GenericSurface")]
+        struct Repr<'a, Slice> {
+            slice: &'a Slice,
+            size: Size,
+            stride: u32,
+        }
+
+        Repr {
+            slice: &self.slice,
+            size: self.size,
+            stride: self.stride,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Slice, Item> serde::Deserialize<'de> for GenericSurface<Slice, Item>
+where
+    Slice: serde::Deserialize<'de> + AsRef<[Item]>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "GenericSurface")]
+        struct Repr<Slice> {
+            slice: Slice,
+            size: Size,
+            // surfaces serialized before strides were stored don't have it
+            #[serde(default)]
+            stride: Option<u32>,
+        }
+
+        let Repr {
+            slice,
+            size,
+            stride,
+        } = Repr::<Slice>::deserialize(deserializer)?;
+
+        let stride = stride.unwrap_or(size.x);
+        let expected = match size.y.checked_sub(1) {
+            Some(last_row) => (last_row as usize)
+                .checked_mul(stride as usize)
+                .and_then(|len| len.checked_add(size.x as usize)),
+            None => Some(0),
+        };
+        let len = slice.as_ref().len();
+
+        if expected != Some(len) {
+            return Err(serde::de::Error::custom(format_args!(
+                "surface slice has {len} values, which doesn't match its size and stride"
+            )));
+        }
+
+        Self::with_stride(slice, size, stride)
+            .ok_or_else(|| serde::de::Error::custom("surface stride is smaller than its width"))
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<'a, Item> GenericSurface<&'a [Item], Item>
+where
+    Item: bytemuck::Pod,
+{
+    /// Construct a new surface that reinterprets `bytes` as values, without copying.
+    ///
+    /// Returns `None` if `bytes` isn't aligned for `Item`, if its length isn't a multiple of the size of `Item`
+    /// or if it doesn't hold exactly `size.x * size.y` values.
+    #[inline]
+    pub fn from_bytes(bytes: &'a [u8], size: Size) -> Option<Self> {
+        Self::new(bytemuck::try_cast_slice(bytes).ok()?, size)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<'a, Item> GenericSurface<&'a mut [Item], Item>
+where
+    Item: bytemuck::Pod,
+{
+    /// Construct a new mutable surface that reinterprets `bytes` as values, without copying.
+    ///
+    /// Returns `None` under the same conditions as [`GenericSurface::from_bytes`].
+    #[inline]
+    pub fn from_bytes_mut(bytes: &'a mut [u8], size: Size) -> Option<Self> {
+        Self::new(bytemuck::try_cast_slice_mut(bytes).ok()?, size)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Item> GenericSurface<Vec<Item>, Item> {
+    #[track_caller]
+    fn owned_len(size: Size) -> usize {
+        (size.x as usize)
+            .checked_mul(size.y as usize)
+            .expect("surface size overflows usize")
+    }
+
+    /// Construct a new surface that owns its values, filled with clones of `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't fit in `usize` (only possible on 32-bit and smaller targets).
+    #[inline]
+    #[track_caller]
+    pub fn new_filled(size: Size, value: Item) -> Self
+    where
+        Item: Clone,
+    {
+        Self {
+            slice: alloc::vec![value; Self::owned_len(size)],
+            size,
+            stride: size.x,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Construct a new surface that owns its values, filled with default values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't fit in `usize` (only possible on 32-bit and smaller targets).
+    #[inline]
+    #[track_caller]
+    pub fn new_default(size: Size) -> Self
+    where
+        Item: Default,
+    {
+        Self {
+            slice: core::iter::repeat_with(Item::default)
+                .take(Self::owned_len(size))
+                .collect(),
+            size,
+            stride: size.x,
+            ghost: PhantomData,
+        }
+    }
+}
+
+/// Copy-on-write surfaces.
+///
+/// `GenericSurface<Cow<[Item]>, Item>` can be read like any other surface, but `Cow` can't implement `AsMut`
+/// (it may have to clone first), so it has to be made mutable explicitly with [`GenericSurface::to_mut`].
+#[cfg(feature = "alloc")]
+impl<Item> GenericSurface<Cow<'_, [Item]>, Item>
+where
+    Item: Clone,
+{
+    /// Returns a mutable view of this surface, cloning the values first if they're still borrowed.
+    ///
+    /// The clone keeps the row padding, so the size and stride stay the same.
+    #[inline]
+    pub fn to_mut(&mut self) -> GenericSurface<&mut [Item], Item> {
+        GenericSurface {
+            slice: self.slice.to_mut().as_mut_slice(),
+            size: self.size,
+            stride: self.stride,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Checks whether the values are owned, i.e. were cloned by [`GenericSurface::to_mut`] or were never borrowed.
+    #[inline]
+    pub fn is_owned(&self) -> bool {
+        matches!(self.slice, Cow::Owned(_))
+    }
+
+    /// Returns a surface that owns the values, cloning them if they're still borrowed.
+    #[inline]
+    pub fn into_owned(self) -> GenericSurface<Vec<Item>, Item> {
+        GenericSurface {
+            slice: self.slice.into_owned(),
+            size: self.size,
+            stride: self.stride,
+            ghost: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Slice, Item> GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]>,
+    Item: Clone,
+{
+    /// Copy the values into a new surface that owns them.
+    ///
+    /// The row padding (if the surface has a stride) is not copied.
+    #[inline]
+    pub fn to_owned(&self) -> GenericSurface<Vec<Item>, Item> {
+        GenericSurface {
+            slice: self.rows().flatten().cloned().collect(),
+            size: self.size,
+            stride: self.size.x,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Copy a rectangular part of this surface into a new tightly packed surface that owns its values.
+    ///
+    /// Returns `None` if the rectangle extends past the surface.
+    pub fn cropped(&self, offset: Point, size: Size) -> Option<GenericSurface<Vec<Item>, Item>> {
+        if !rect_fits(offset, size, self.size) {
+            return None;
+        }
+
+        let slice = self.slice.as_ref();
+        let mut values = Vec::with_capacity(size.x as usize * size.y as usize);
+
+        for y in offset.y..offset.y + size.y {
+            let start = y as usize * self.stride as usize + offset.x as usize;
+            values.extend_from_slice(&slice[start..start + size.x as usize]);
+        }
+
+        GenericSurface::new(values, size)
+    }
+}
+
+/// One of the halves returned by [`GenericSurface::split_at_row`].
+type SurfaceHalf<'a, Item> = GenericSurface<&'a mut [Item], Item>;
+
+impl<Slice, Item> GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]> + AsMut<[Item]>,
+{
+    /// Returns an iterator over the rows of this surface, each `size.x` values long.
+    ///
+    /// The row padding (if the surface has a stride) is not included.
+    #[inline]
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Item]> {
+        let (width, stride) = (self.size.x as usize, self.stride as usize);
+        let mut rest = self.slice.as_mut();
+
+        // the last row may be shorter than the stride
+        (0..self.size.y).map(move |_| {
+            let line = core::mem::take(&mut rest);
+            let (row, tail) = line.split_at_mut(stride.min(line.len()));
+            rest = tail;
+            &mut row[..width]
+        })
+    }
+
+    /// Swap the values at `a` and `b`.
+    ///
+    /// Returns `false` (and does nothing) if either point is out of bounds.
+    #[inline]
+    pub fn swap(&mut self, a: Point, b: Point) -> bool {
+        match (self.index_of(a), self.index_of(b)) {
+            (Some(a), Some(b)) => {
+                self.slice.as_mut().swap(a, b);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Splits this surface into two mutable surfaces at row `y`: the rows before it and the rows starting from it.
+    ///
+    /// This is the 2D analogue of [`slice::split_at_mut`]: the halves don't overlap,
+    /// so one can be blitted to the other even though both are parts of the same surface.
+    /// Both keep the stride of this surface.
+    /// There is no column split, since the left and right parts of the rows are interleaved in the slice
+    /// and can't be borrowed separately; use [`GenericSurface::copy_within`] to copy between columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is greater than the height.
+    #[track_caller]
+    pub fn split_at_row(&mut self, y: u32) -> (SurfaceHalf<'_, Item>, SurfaceHalf<'_, Item>) {
+        assert!(y <= self.size.y, "split row out of bounds");
+
+        let mid = self.offset_of(point(0, y));
+        let slice = self.slice.as_mut();
+        // the last row may be unpadded, so an empty bottom half can start past the end
+        let (top, bottom) = slice.split_at_mut(mid.min(slice.len()));
+
+        (
+            GenericSurface {
+                slice: top,
+                size: size(self.size.x, y),
+                stride: self.stride,
+                ghost: PhantomData,
+            },
+            GenericSurface {
+                slice: bottom,
+                size: size(self.size.x, self.size.y - y),
+                stride: self.stride,
+                ghost: PhantomData,
+            },
+        )
+    }
+
+    /// Copies a rectangular part of this surface to another position on it.
+    ///
+    /// The source and destination parts may overlap.
+    /// This is the 2D analogue of [`slice::copy_within`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if either part extends past the surface.
+    #[track_caller]
+    pub fn copy_within(&mut self, src_offset: Point, dest_offset: Point, size: Size)
+    where
+        Item: Copy,
+    {
+        assert!(
+            rect_fits(src_offset, size, self.size) && rect_fits(dest_offset, size, self.size),
+            "copied rectangle out of bounds"
+        );
+
+        let stride = self.stride as usize;
+        let slice = self.slice.as_mut();
+
+        let mut copy_row = |y: u32| {
+            let src_start = (src_offset.y + y) as usize * stride + src_offset.x as usize;
+            let dest_start = (dest_offset.y + y) as usize * stride + dest_offset.x as usize;
+
+            slice.copy_within(src_start..src_start + size.x as usize, dest_start);
+        };
+
+        // copy the rows that are about to be overwritten first
+        if dest_offset.y > src_offset.y {
+            (0..size.y).rev().for_each(&mut copy_row);
+        } else {
+            (0..size.y).for_each(&mut copy_row);
+        }
+    }
+
+    /// Returns an iterator over the positions and mutable values of this surface in row-major order.
+    #[inline]
+    pub fn pixels_mut(&mut self) -> impl Iterator<Item = (Point, &mut Item)> {
+        self.rows_mut().enumerate().flat_map(move |(y, row)| {
+            row.iter_mut()
+                .enumerate()
+                .map(move |(x, value)| (point(x as u32, y as u32), value))
+        })
+    }
+}
+
+/// Dereferences to the whole slice, including the row padding if the surface has a stride.
+impl<Slice, Item> Deref for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]>,
+{
+    type Target = [Item];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.slice.as_ref()
+    }
+}
+
+impl<Slice, Item> DerefMut for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]> + AsMut<[Item]>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.slice.as_mut()
+    }
+}
+
+impl<Slice, Item> Surface<Item> for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.size
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        let index = self.index_of(pt)?;
+
+        Some(self.slice.as_ref().index(index))
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[Item]> {
+        if y < self.size.y {
+            let start = self.offset_of(point(0, y));
+            Some(&self.slice.as_ref()[start..start + self.size.x as usize])
+        } else {
+            None
+        }
+    }
+}
+
+impl<Slice, Item> SurfaceMut<Item> for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]> + AsMut<[Item]>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
+        let index = self.index_of(pt)?;
+
+        Some(self.slice.as_mut().index_mut(index))
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [Item]> {
+        if y < self.size.y {
+            let start = self.offset_of(point(0, y));
+            Some(&mut self.slice.as_mut()[start..start + self.size.x as usize])
+        } else {
+            None
+        }
+    }
+
+    fn shift(&mut self, delta: mint::Vector2<i32>, fill: Item)
+    where
+        Item: Clone,
+    {
+        let (width, height) = (self.size.x as usize, self.size.y as usize);
+        let stride = self.stride as usize;
+        let dx = (delta.x.unsigned_abs() as usize).min(width);
+        let dy = (delta.y.unsigned_abs() as usize).min(height);
+        let slice = self.slice.as_mut();
+
+        // swapping moves the kept rows without cloning, the rows they're swapped with are filled below
+        let (kept, exposed) = if delta.y > 0 {
+            for y in (dy..height).rev() {
+                let (above, row) = slice.split_at_mut(y * stride);
+                above[(y - dy) * stride..][..width].swap_with_slice(&mut row[..width]);
+            }
+
+            (dy..height, 0..dy)
+        } else if dy > 0 {
+            for y in 0..height - dy {
+                let (above, row) = slice.split_at_mut((y + dy) * stride);
+                above[y * stride..][..width].swap_with_slice(&mut row[..width]);
+            }
+
+            (0..height - dy, height - dy..height)
+        } else {
+            (0..height, 0..0)
+        };
+
+        for y in kept {
+            let row = &mut slice[y * stride..][..width];
+
+            if delta.x > 0 {
+                row.rotate_right(dx);
+                row[..dx].fill(fill.clone());
+            } else {
+                row.rotate_left(dx);
+                row[width - dx..].fill(fill.clone());
+            }
+        }
+
+        for y in exposed {
+            slice[y * stride..][..width].fill(fill.clone());
+        }
+    }
+}
+
+/// Surface with the width and height known at compile time, backed by an array.
+///
+/// The values are stored as rows (`[[T; W]; H]`), as stable Rust can't express `[T; W * H]` yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArraySurface<T, const W: usize, const H: usize> {
+    data: [[T; W]; H],
+}
+
+impl<T, const W: usize, const H: usize> ArraySurface<T, W, H> {
+    /// Construct a new surface.
+    #[inline]
+    pub const fn new(data: [[T; W]; H]) -> Self {
+        Self { data }
+    }
+
+    /// Returns a reference to the underlying array.
+    #[inline]
+    pub fn data(&self) -> &[[T; W]; H] {
+        &self.data
+    }
+
+    /// Returns a reference to the underlying array.
+    #[inline]
+    pub fn data_mut(&mut self) -> &mut [[T; W]; H] {
+        &mut self.data
+    }
+
+    /// Returns the underlying array.
+    #[inline]
+    pub fn into_inner(self) -> [[T; W]; H] {
+        self.data
+    }
+}
+
+impl<T, const W: usize, const H: usize> Surface<T> for ArraySurface<T, W, H> {
+    #[inline]
+    fn surface_size(&self) -> Size {
+        size(W as u32, H as u32)
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        self.data.surface_get(pt)
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[T]> {
+        self.data.surface_row(y)
+    }
+}
+
+impl<T, const W: usize, const H: usize> SurfaceMut<T> for ArraySurface<T, W, H> {
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        self.data.surface_get_mut(pt)
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [T]> {
+        self.data.surface_row_mut(y)
+    }
+}
+
+/// The array is indexed as `array[y][x]`, like [`ArraySurface`].
+impl<T, const W: usize, const H: usize> Surface<T> for [[T; W]; H] {
+    #[inline]
+    fn surface_size(&self) -> Size {
+        size(W as u32, H as u32)
+    }
+
+    // the slice methods are called explicitly because `Surface::get` takes precedence on arrays
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        <[[T; W]]>::get(self, pt.y as usize).and_then(|row| <[T]>::get(row, pt.x as usize))
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[T]> {
+        <[[T; W]]>::get(self, y as usize).map(|row| row.as_slice())
+    }
+}
+
+impl<T, const W: usize, const H: usize> SurfaceMut<T> for [[T; W]; H] {
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut T> {
+        <[[T; W]]>::get_mut(self, pt.y as usize).and_then(|row| <[T]>::get_mut(row, pt.x as usize))
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [T]> {
+        <[[T; W]]>::get_mut(self, y as usize).map(|row| row.as_mut_slice())
+    }
+}
+
+impl<Slice, Item> Index<Point> for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]>,
+{
+    type Output = Item;
+
+    /// Panics if the point is out of bounds, use [`Surface::surface_get`] to avoid that.
+    #[inline]
+    fn index(&self, pt: Point) -> &Self::Output {
+        index_or_panic(self.surface_get(pt), pt, self.size)
+    }
+}
+
+impl<Slice, Item> IndexMut<Point> for GenericSurface<Slice, Item>
+where
+    Slice: AsRef<[Item]> + AsMut<[Item]>,
+{
+    /// Panics if the point is out of bounds, use [`SurfaceMut::surface_get_mut`] to avoid that.
+    #[inline]
+    fn index_mut(&mut self, pt: Point) -> &mut Self::Output {
+        let size = self.size;
+        index_or_panic(self.surface_get_mut(pt), pt, size)
+    }
+}
+
+/// Indexing with the slice indices goes to the whole slice, like the [`Deref`] implementation,
+/// so `surface[i]` and `&surface[1..3]` work the same as on the slice.
+macro_rules! impl_slice_index {
+    ($($index:ty => $output:ty),* $(,)?) => {
+        $(
+            impl<Slice, Item> Index<$index> for GenericSurface<Slice, Item>
+            where
+                Slice: AsRef<[Item]>,
+            {
+                type Output = $output;
+
+                #[inline]
+                fn index(&self, index: $index) -> &Self::Output {
+                    &self.slice.as_ref()[index]
+                }
+            }
+
+            impl<Slice, Item> IndexMut<$index> for GenericSurface<Slice, Item>
+            where
+                Slice: AsRef<[Item]> + AsMut<[Item]>,
+            {
+                #[inline]
+                fn index_mut(&mut self, index: $index) -> &mut Self::Output {
+                    &mut self.slice.as_mut()[index]
+                }
+            }
+        )*
+    };
+}
+
+impl_slice_index!(
+    usize => Item,
+    Range<usize> => [Item],
+    RangeFrom<usize> => [Item],
+    RangeTo<usize> => [Item],
+    RangeFull => [Item],
+    RangeInclusive<usize> => [Item],
+    RangeToInclusive<usize> => [Item],
+    (Bound<usize>, Bound<usize>) => [Item],
+);
+
+/// Checks whether the rectangle at `offset` of size `size` is inside `bounds` (without overflowing).
+#[inline]
+fn rect_fits(offset: Point, size: Size, bounds: Size) -> bool {
+    offset.x.checked_add(size.x).is_some_and(|x| x <= bounds.x)
+        && offset.y.checked_add(size.y).is_some_and(|y| y <= bounds.y)
+}
+
+#[inline]
+#[track_caller]
+fn index_or_panic<T>(value: Option<T>, pt: Point, size: Size) -> T {
+    match value {
+        Some(value) => value,
+        None => panic!(
+            "point out of bounds: the size is ({}, {}) but the point is ({}, {})",
+            size.x, size.y, pt.x, pt.y
+        ),
+    }
+}
+
+/// A 'surface' that holds a single value, like a plain-colored rectangle.
+///
+/// It's meant to be used as a source. It can be a destination too, but every point refers to the same value,
+/// so blitting into it collapses all the writes into one: only the last written pixel is left.
+/// Change the value with [`SingleValueSurface::value_mut`], and blit into a [`GenericSurface`] if you need to keep every pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SingleValueSurface<T> {
+    /// Surface size.
+    pub size: Size,
+    /// Stored value, likely a color.
+    pub value: T,
+}
+
+impl<T> SingleValueSurface<T> {
+    /// Construct a new surface.
+    #[inline]
+    pub const fn new(value: T, size: Size) -> Self {
+        Self { size, value }
+    }
+
+    /// Returns a reference to the underlying value.
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns a reference to the underlying value.
+    #[inline]
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Returns the underlying value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Surface<T> for SingleValueSurface<T> {
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.size
+    }
+
+    #[inline]
+    fn surface_get(&self, _pt: Point) -> Option<&T> {
+        Some(&self.value)
+    }
+}
+
+/// All points share the same value, so writes to different points overwrite each other.
+impl<T> SurfaceMut<T> for SingleValueSurface<T> {
+    #[inline]
+    fn surface_get_mut(&mut self, _pt: Point) -> Option<&mut T> {
+        Some(&mut self.value)
+    }
+}
+
+/// A 'surface' that alternates between two values in a checkerboard pattern, like a transparency backdrop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheckerSurface<T> {
+    /// Surface size.
+    pub size: Size,
+    /// Size of a single checkerboard cell.
+    pub cell: Size,
+    /// Value of the top-left cell and every other cell after it.
+    pub a: T,
+    /// Value of the remaining cells.
+    pub b: T,
+}
+
+impl<T> CheckerSurface<T> {
+    /// Construct a new surface.
+    #[inline]
+    pub const fn new(size: Size, cell: Size, a: T, b: T) -> Self {
+        Self { size, cell, a, b }
+    }
+}
+
+impl<T> Surface<T> for CheckerSurface<T> {
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.size
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        if size_contains(self.size, pt) && self.cell.x != 0 && self.cell.y != 0 {
+            // comparing the parities can't overflow, unlike adding the cell coordinates
+            if (pt.x / self.cell.x) % 2 == (pt.y / self.cell.y) % 2 {
+                Some(&self.a)
+            } else {
+                Some(&self.b)
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// A surface that computes its values with a closure, like a procedural pattern.
+///
+/// Since [`Surface::surface_get`] returns references, the closure returns references too,
+/// usually into a lookup table (e.g. a gradient or a palette) that outlives the surface.
+/// Returning a freshly computed value would require a cache for the reference to point to,
+/// which can't be done soundly if several references are alive at once.
+#[derive(Clone, Copy)]
+pub struct FnSurface<'a, F, T> {
+    size: Size,
+    func: F,
+    ghost: PhantomData<&'a T>,
+}
+
+impl<'a, F, T> FnSurface<'a, F, T>
+where
+    F: Fn(Point) -> &'a T,
+{
+    /// Construct a new surface.
+    ///
+    /// `func` is only called with points inside `size`.
+    #[inline]
+    pub const fn new(size: Size, func: F) -> Self {
+        Self {
+            size,
+            func,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns the underlying closure.
+    #[inline]
+    pub fn into_inner(self) -> F {
+        self.func
+    }
+}
+
+impl<'a, F, T> Surface<T> for FnSurface<'a, F, T>
+where
+    F: Fn(Point) -> &'a T,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.size
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&T> {
+        if size_contains(self.size, pt) {
+            Some((self.func)(pt))
+        } else {
+            None
+        }
+    }
+}
+
+/// A surface that views the values of another surface through a closure, like a palette lookup.
+///
+/// The closure returns references for the same reason as [`FnSurface`]'s one:
+/// a per-call cache would be unsound behind `&self`, and an additional value-returning method
+/// would split the API in two, with only part of it working with [`blit`](crate::blit) and the views.
+#[derive(Clone, Copy)]
+pub struct MapSurface<'a, S, F, T, U> {
+    surface: S,
+    func: F,
+    ghost: PhantomData<(&'a U, T)>,
+}
+
+impl<'a, S, F, T, U> MapSurface<'a, S, F, T, U>
+where
+    S: Surface<T>,
+    F: Fn(&T) -> &'a U,
+{
+    /// Create a new `MapSurface`.
+    #[inline]
+    pub fn new(surface: S, func: F) -> Self {
+        Self {
+            surface,
+            func,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+impl<'a, S, F, T, U> Surface<U> for MapSurface<'a, S, F, T, U>
+where
+    S: Surface<T>,
+    F: Fn(&T) -> &'a U,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.surface.surface_size()
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&U> {
+        self.surface.surface_get(pt).map(&self.func)
+    }
+}
+
+/// A surface that only uses a rectangular part of another surface.
+///
+/// With the `serde` feature, deserializing fails if the rectangle doesn't fit inside the underlying surface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubSurface<S, Item> {
+    surface: S,
+    offset: Point,
+    size: Size,
+    ghost: PhantomData<Item>,
+}
+
+#[cfg(feature = "serde")]
+impl<S, Item> serde::Serialize for SubSurface<S, Item>
+where
+    S: serde::Serialize,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        #[derive(serde::Serialize)]
+        #[serde(rename = "SubSurface")]
+        struct Repr<'a, S> {
+            surface: &'a S,
+            offset: Point,
+            size: Size,
+        }
+
+        Repr {
+            surface: &self.surface,
+            offset: self.offset,
+            size: self.size,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S, Item> serde::Deserialize<'de> for SubSurface<S, Item>
+where
+    S: serde::Deserialize<'de> + Surface<Item>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "SubSurface")]
+        struct Repr<S> {
+            surface: S,
+            offset: Point,
+            size: Size,
+        }
+
+        let Repr {
+            surface,
+            offset,
+            size,
+        } = Repr::<S>::deserialize(deserializer)?;
+
+        if rect_fits(offset, size, surface.surface_size()) {
+            Ok(Self {
+                surface,
+                offset,
+                size,
+                ghost: PhantomData,
+            })
+        } else {
+            Err(serde::de::Error::custom(
+                "sub-surface rectangle doesn't fit inside the underlying surface",
+            ))
+        }
+    }
+}
+
+impl<S, Item> SubSurface<S, Item> {
+    /// Position of the rectangular part on the original surface.
+    #[inline]
+    pub fn offset(&self) -> Point {
+        self.offset
+    }
+
+    /// Size of the rectangular part (size of the `SubSurface` itself).
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The rectangular part of the original surface, combining [`SubSurface::offset`] and [`SubSurface::size`].
+    #[inline]
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.offset, self.size)
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+impl<S, Item> SubSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    /// Create a new `SubSurface`.
+    ///
+    /// The size is clamped so that the rectangular part doesn't extend past the underlying surface.
+    #[inline]
+    pub fn new(surface: S, offset: Point, size: Size) -> Self {
+        let inner_size = surface.surface_size();
+
+        Self {
+            size: self::size(
+                size.x.min(inner_size.x.saturating_sub(offset.x)),
+                size.y.min(inner_size.y.saturating_sub(offset.y)),
+            ),
+            surface,
+            offset,
+            ghost: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S, Item> SubSurface<S, Item>
+where
+    S: Surface<Item>,
+    Item: Clone,
+{
+    /// Copy the values of the rectangular part into a new surface that owns them.
+    ///
+    /// Returns `None` if the underlying surface doesn't have a value somewhere in the rectangle.
+    pub fn to_owned(&self) -> Option<GenericSurface<Vec<Item>, Item>> {
+        let mut values = Vec::with_capacity(self.size.x as usize * self.size.y as usize);
+
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                values.push(self.surface_get(point(x, y))?.clone());
+            }
+        }
+
+        GenericSurface::new(values, self.size)
+    }
+}
+
+impl<S, Item> Surface<Item> for SubSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.size
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        if size_contains(self.size, pt) {
+            self.surface
+                .surface_get(point(pt.x + self.offset.x, pt.y + self.offset.y))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[Item]> {
+        if y < self.size.y {
+            let row = self.surface.surface_row(y + self.offset.y)?;
+            row.get(self.offset.x as usize..(self.offset.x + self.size.x) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> SurfaceMut<Item> for SubSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
+        if size_contains(self.size, pt) {
+            self.surface
+                .surface_get_mut(point(pt.x + self.offset.x, pt.y + self.offset.y))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn surface_row_mut(&mut self, y: u32) -> Option<&mut [Item]> {
+        if y < self.size.y {
+            let row = self.surface.surface_row_mut(y + self.offset.y)?;
+            row.get_mut(self.offset.x as usize..(self.offset.x + self.size.x) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> Index<Point> for SubSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    type Output = Item;
+
+    /// Panics if the point is out of bounds, use [`Surface::surface_get`] to avoid that.
+    #[inline]
+    fn index(&self, pt: Point) -> &Self::Output {
+        index_or_panic(self.surface_get(pt), pt, self.size)
+    }
+}
+
+impl<S, Item> IndexMut<Point> for SubSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+{
+    /// Panics if the point is out of bounds, use [`SurfaceMut::surface_get_mut`] to avoid that.
+    #[inline]
+    fn index_mut(&mut self, pt: Point) -> &mut Self::Output {
+        let size = self.size;
+        index_or_panic(self.surface_get_mut(pt), pt, size)
+    }
+}
+
+/// A surface that views another surface flipped horizontally and/or vertically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlipSurface<S, Item> {
+    surface: S,
+    flip_x: bool,
+    flip_y: bool,
+    ghost: PhantomData<Item>,
+}
+
+impl<S, Item> FlipSurface<S, Item> {
+    /// Create a new `FlipSurface`.
+    #[inline]
+    pub fn new(surface: S, flip_x: bool, flip_y: bool) -> Self {
+        Self {
+            surface,
+            flip_x,
+            flip_y,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Whether the surface is flipped horizontally.
+    #[inline]
+    pub fn is_flipped_x(&self) -> bool {
+        self.flip_x
+    }
+
+    /// Whether the surface is flipped vertically.
+    #[inline]
+    pub fn is_flipped_y(&self) -> bool {
+        self.flip_y
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+impl<S, Item> FlipSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn inner_point(&self, pt: Point) -> Option<Point> {
+        let size = self.surface.surface_size();
+
+        if size_contains(size, pt) {
+            Some(point(
+                if self.flip_x {
+                    reversed(pt.x, size.x)
+                } else {
+                    pt.x
+                },
+                if self.flip_y {
+                    reversed(pt.y, size.y)
+                } else {
+                    pt.y
+                },
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> Surface<Item> for FlipSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.surface.surface_size()
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        self.inner_point(pt)
+            .and_then(|pt| self.surface.surface_get(pt))
+    }
+}
+
+impl<S, Item> SurfaceMut<Item> for FlipSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
+        self.inner_point(pt)
+            .and_then(|pt| self.surface.surface_get_mut(pt))
+    }
+}
+
+/// A surface that views another surface transposed (with x and y swapped).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransposeSurface<S, Item> {
+    surface: S,
+    ghost: PhantomData<Item>,
+}
+
+impl<S, Item> TransposeSurface<S, Item> {
+    /// Create a new `TransposeSurface`.
+    #[inline]
+    pub fn new(surface: S) -> Self {
+        Self {
+            surface,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+impl<S, Item> Surface<Item> for TransposeSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        let inner_size = self.surface.surface_size();
+        size(inner_size.y, inner_size.x)
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        self.surface.surface_get(point(pt.y, pt.x))
+    }
+}
+
+impl<S, Item> SurfaceMut<Item> for TransposeSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
+        self.surface.surface_get_mut(point(pt.y, pt.x))
+    }
+}
+
+/// A surface that repeats another surface over an arbitrary area.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TileSurface<S, Item> {
+    surface: S,
+    size: Size,
+    ghost: PhantomData<Item>,
+}
+
+impl<S, Item> TileSurface<S, Item> {
+    /// Create a new `TileSurface`.
+    #[inline]
+    pub fn new(surface: S, size: Size) -> Self {
+        Self {
+            surface,
+            size,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Size of the tiled area (size of the `TileSurface` itself).
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+impl<S, Item> Surface<Item> for TileSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.size
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        let tile_size = self.surface.surface_size();
+
+        if size_contains(self.size, pt) && tile_size.x != 0 && tile_size.y != 0 {
+            self.surface
+                .surface_get(point(pt.x % tile_size.x, pt.y % tile_size.y))
+        } else {
+            None
+        }
+    }
+}
+
+/// Surface that also has values for positions outside of it, like the edge handling views
+/// [`EdgeClampSurface`], [`WrapSurface`] and [`MirrorSurface`].
+///
+/// Used by filters like [`convolve_separable`](crate::convolve_separable) to read past the borders.
+pub trait BorderSurface<T>: Surface<T> {
+    /// Get a value at (pt.x, pt.y), which may be outside of the surface.
+    fn border_get(&self, pt: SignedPoint) -> Option<&T>;
+}
+
+impl<S, T> BorderSurface<T> for &S
+where
+    S: BorderSurface<T>,
+{
+    #[inline]
+    fn border_get(&self, pt: SignedPoint) -> Option<&T> {
+        (**self).border_get(pt)
+    }
+}
+
+impl<S, T> BorderSurface<T> for &mut S
+where
+    S: BorderSurface<T>,
+{
+    #[inline]
+    fn border_get(&self, pt: SignedPoint) -> Option<&T> {
+        (**self).border_get(pt)
+    }
+}
+
+/// A surface that clamps the positions outside of another surface to its nearest edge value.
+///
+/// Reports the size of the inner surface, but `surface_get` returns the closest edge value
+/// for positions past the right and bottom edges instead of `None`
+/// (and [`EdgeClampSurface::get_clamped`] does the same for negative positions).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeClampSurface<S, Item> {
+    surface: S,
+    ghost: PhantomData<Item>,
+}
+
+impl<S, Item> EdgeClampSurface<S, Item> {
+    /// Create a new `EdgeClampSurface`.
+    #[inline]
+    pub fn new(surface: S) -> Self {
+        Self {
+            surface,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+impl<S, Item> EdgeClampSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    /// Get the value at `pt` (which may be negative), clamped to the nearest edge.
+    ///
+    /// Returns `None` only if the inner surface is empty.
+    #[inline]
+    pub fn get_clamped(&self, pt: SignedPoint) -> Option<&Item> {
+        self.surface_get(point(pt.x.max(0) as u32, pt.y.max(0) as u32))
+    }
+}
+
+impl<S, Item> Surface<Item> for EdgeClampSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.surface.surface_size()
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        let size = self.surface.surface_size();
+
+        if size.x != 0 && size.y != 0 {
+            self.surface
+                .surface_get(point(pt.x.min(size.x - 1), pt.y.min(size.y - 1)))
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> BorderSurface<Item> for EdgeClampSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn border_get(&self, pt: SignedPoint) -> Option<&Item> {
+        self.get_clamped(pt)
+    }
+}
+
+/// A surface that wraps the positions outside of another surface around it (toroidally).
+///
+/// Unlike [`TileSurface`], it reports the size of the inner surface,
+/// but `surface_get` takes the positions modulo that size instead of returning `None`
+/// (and [`WrapSurface::get_wrapped`] does the same for negative positions).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WrapSurface<S, Item> {
+    surface: S,
+    ghost: PhantomData<Item>,
+}
+
+impl<S, Item> WrapSurface<S, Item> {
+    /// Create a new `WrapSurface`.
+    #[inline]
+    pub fn new(surface: S) -> Self {
+        Self {
+            surface,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+impl<S, Item> WrapSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    /// Position inside the inner surface that `pt` wraps to, `None` if it's empty.
+    #[inline]
+    fn wrapped(&self, pt: SignedPoint) -> Option<Point> {
+        let size = self.surface.surface_size();
+
+        (size.x != 0 && size.y != 0).then(|| {
+            point(
+                i64::from(pt.x).rem_euclid(i64::from(size.x)) as u32,
+                i64::from(pt.y).rem_euclid(i64::from(size.y)) as u32,
+            )
+        })
+    }
+
+    /// Get the value at `pt` (which may be negative), wrapped around the surface.
+    ///
+    /// Returns `None` only if the inner surface is empty.
+    #[inline]
+    pub fn get_wrapped(&self, pt: SignedPoint) -> Option<&Item> {
+        self.surface.surface_get(self.wrapped(pt)?)
+    }
+}
+
+impl<S, Item> WrapSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+{
+    /// Get a mutable value at `pt` (which may be negative), wrapped around the surface.
+    ///
+    /// Returns `None` only if the inner surface is empty.
+    #[inline]
+    pub fn get_wrapped_mut(&mut self, pt: SignedPoint) -> Option<&mut Item> {
+        let pt = self.wrapped(pt)?;
+
+        self.surface.surface_get_mut(pt)
+    }
+}
+
+impl<S, Item> Surface<Item> for WrapSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.surface.surface_size()
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        let size = self.surface.surface_size();
+
+        if size.x != 0 && size.y != 0 {
+            self.surface
+                .surface_get(point(pt.x % size.x, pt.y % size.y))
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> SurfaceMut<Item> for WrapSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
+        let size = self.surface.surface_size();
+
+        if size.x != 0 && size.y != 0 {
+            self.surface
+                .surface_get_mut(point(pt.x % size.x, pt.y % size.y))
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> BorderSurface<Item> for WrapSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn border_get(&self, pt: SignedPoint) -> Option<&Item> {
+        self.get_wrapped(pt)
+    }
+}
+
+/// A surface that reflects the positions outside of another surface back into it, repeating the edge values
+/// (so `-1` maps to `0` and `size` maps to `size - 1`).
+///
+/// This is the symmetric boundary mode commonly used by blur and edge detection filters.
+/// Reports the size of the inner surface, but `surface_get` returns the reflected value
+/// for positions past the right and bottom edges instead of `None`
+/// (and [`MirrorSurface::get_mirrored`] does the same for negative positions).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MirrorSurface<S, Item> {
+    surface: S,
+    ghost: PhantomData<Item>,
+}
+
+impl<S, Item> MirrorSurface<S, Item> {
+    /// Create a new `MirrorSurface`.
+    #[inline]
+    pub fn new(surface: S) -> Self {
+        Self {
+            surface,
+            ghost: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
+    }
+}
+
+/// Reflect `coord` into `0..len` (which must not be empty), repeating the edge values.
+#[inline]
+fn mirrored_coord(coord: i64, len: u32) -> u32 {
+    let len = i64::from(len);
+    let coord = coord.rem_euclid(2 * len);
+
+    (if coord < len {
+        coord
+    } else {
+        2 * len - 1 - coord
+    }) as u32
+}
+
+impl<S, Item> MirrorSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    /// Get the value at `pt` (which may be negative), reflected back into the surface.
+    ///
+    /// Returns `None` only if the inner surface is empty.
+    #[inline]
+    pub fn get_mirrored(&self, pt: SignedPoint) -> Option<&Item> {
+        let size = self.surface.surface_size();
+
+        if size.x != 0 && size.y != 0 {
+            self.surface.surface_get(point(
+                mirrored_coord(pt.x.into(), size.x),
+                mirrored_coord(pt.y.into(), size.y),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> Surface<Item> for MirrorSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        self.surface.surface_size()
+    }
+
+    #[inline]
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        let size = self.surface.surface_size();
+
+        if size.x != 0 && size.y != 0 {
+            self.surface.surface_get(point(
+                mirrored_coord(pt.x.into(), size.x),
+                mirrored_coord(pt.y.into(), size.y),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl<S, Item> BorderSurface<Item> for MirrorSurface<S, Item>
+where
+    S: Surface<Item>,
+{
+    #[inline]
+    fn border_get(&self, pt: SignedPoint) -> Option<&Item> {
+        self.get_mirrored(pt)
+    }
+}
+
+/// A surface that presents two surfaces side by side (`first` on the left).
+///
+/// Can be nested to concatenate more surfaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConcatH<A, B, Item> {
+    first: A,
+    second: B,
+    ghost: PhantomData<Item>,
+}
+
+impl<A, B, Item> ConcatH<A, B, Item>
+where
+    A: Surface<Item>,
+    B: Surface<Item>,
+{
+    /// Create a new `ConcatH`.
+    ///
+    /// Returns `None` if the surface heights are not equal, or if the sum of the widths doesn't fit in `u32`.
+    #[inline]
+    pub fn new(first: A, second: B) -> Option<Self> {
+        let (first_size, second_size) = (first.surface_size(), second.surface_size());
+
+        if first_size.y == second_size.y && first_size.x.checked_add(second_size.x).is_some() {
+            Some(Self {
+                first,
+                second,
+                ghost: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns references to the underlying surfaces.
+    #[inline]
+    pub fn inner(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Returns references to the underlying surfaces.
+    #[inline]
+    pub fn inner_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
+    }
+
+    /// Returns the underlying surfaces.
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A, B, Item> Surface<Item> for ConcatH<A, B, Item>
+where
+    A: Surface<Item>,
+    B: Surface<Item>,
+{
+    #[inline]
+    fn surface_size(&self) -> Size {
+        let mut size = self.first.surface_size();
+        size.x += self.second.surface_size().x;
+        size
+    }
+
+    #[inline]
+    fn surface_get(&self, mut pt: Point) -> Option<&Item> {
+        let first_size = self.first.surface_size();
+
+        if pt.x < first_size.x {
+            self.first.surface_get(pt)
+        } else {
+            pt.x -= first_size.x;
+            self.second.surface_get(pt)
+        }
+    }
+}
+
+impl<A, B, Item> SurfaceMut<Item> for ConcatH<A, B, Item>
+where
+    A: SurfaceMut<Item>,
+    B: SurfaceMut<Item>,
+{
+    #[inline]
+    fn surface_get_mut(&mut self, mut pt: Point) -> Option<&mut Item> {
+        let first_size = self.first.surface_size();
+
+        if pt.x < first_size.x {
+            self.first.surface_get_mut(pt)
+        } else {
+            pt.x -= first_size.x;
+            self.second.surface_get_mut(pt)
+        }
+    }
+}
+
+/// A surface that presents two surfaces stacked (`first` on the top).
+///
+/// Can be nested to concatenate more surfaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConcatV<A, B, Item> {
+    first: A,
+    second: B,
+    ghost: PhantomData<Item>,
+}
+
+impl<A, B, Item> ConcatV<A, B, Item>
+where
+    A: Surface<Item>,
+    B: Surface<Item>,
+{
+    /// Create a new `ConcatV`.
+    ///
+    /// Returns `None` if the surface widths are not equal, or if the sum of the heights doesn't fit in `u32`.
+    #[inline]
+    pub fn new(first: A, second: B) -> Option<Self> {
+        let (first_size, second_size) = (first.surface_size(), second.surface_size());
+
+        if first_size.x == second_size.x && first_size.y.checked_add(second_size.y).is_some() {
+            Some(Self {
+                first,
+                second,
+                ghost: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns references to the underlying surfaces.
+    #[inline]
+    pub fn inner(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Returns references to the underlying surfaces.
+    #[inline]
+    pub fn inner_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
     }
 
+    /// Returns the underlying surfaces.
     #[inline]
-    fn surface_get(&self, pt: Point) -> Option<&T> {
-        (**self).surface_get(pt)
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
     }
 }
 
-impl<S, T> Surface<T> for &mut S
+impl<A, B, Item> Surface<Item> for ConcatV<A, B, Item>
 where
-    S: Surface<T>,
+    A: Surface<Item>,
+    B: Surface<Item>,
 {
     #[inline]
     fn surface_size(&self) -> Size {
-        (**self).surface_size()
+        let mut size = self.first.surface_size();
+        size.y += self.second.surface_size().y;
+        size
     }
 
     #[inline]
-    fn surface_get(&self, pt: Point) -> Option<&T> {
-        (**self).surface_get(pt)
-    }
-}
+    fn surface_get(&self, mut pt: Point) -> Option<&Item> {
+        let first_size = self.first.surface_size();
 
-/// 2D mutable surface trait.
-pub trait SurfaceMut<T>: Surface<T> {
-    /// Get a mutable value at (pt.x, pt.y).
-    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut T>;
+        if pt.y < first_size.y {
+            self.first.surface_get(pt)
+        } else {
+            pt.y -= first_size.y;
+            self.second.surface_get(pt)
+        }
+    }
 }
 
-impl<S, T> SurfaceMut<T> for &mut S
+impl<A, B, Item> SurfaceMut<Item> for ConcatV<A, B, Item>
 where
-    S: SurfaceMut<T>,
+    A: SurfaceMut<Item>,
+    B: SurfaceMut<Item>,
 {
     #[inline]
-    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut T> {
-        (*self).surface_get_mut(pt)
+    fn surface_get_mut(&mut self, mut pt: Point) -> Option<&mut Item> {
+        let first_size = self.first.surface_size();
+
+        if pt.y < first_size.y {
+            self.first.surface_get_mut(pt)
+        } else {
+            pt.y -= first_size.y;
+            self.second.surface_get_mut(pt)
+        }
     }
 }
 
-/// Generic surface with width and height.
+/// A read-only view of two surfaces of the same size, yielding both values at each position.
+///
+/// It doesn't implement [`Surface`], since there's no pair of values to return a reference to:
+/// use [`ZipSurface::get`] and [`ZipSurface::pixels`] instead (e.g. to diff two surfaces).
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct GenericSurface<Slice, Item> {
-    slice: Slice,
-    size: Size,
-    ghost: PhantomData<Item>,
+pub struct ZipSurface<A, B, ItemA, ItemB> {
+    first: A,
+    second: B,
+    ghost: PhantomData<(ItemA, ItemB)>,
 }
 
-impl<Slice, Item> GenericSurface<Slice, Item>
+impl<A, B, ItemA, ItemB> ZipSurface<A, B, ItemA, ItemB>
 where
-    Slice: AsRef<[Item]>,
+    A: Surface<ItemA>,
+    B: Surface<ItemB>,
 {
-    /// Construct a new surface.
+    /// Create a new `ZipSurface`.
     ///
-    /// Returns `None` if `slice.len() != size.x * size.y`.
+    /// Returns `None` if the surface sizes are not equal.
     #[inline]
-    pub fn new(slice: Slice, size: Size) -> Option<Self> {
-        if slice.as_ref().len() == (size.x * size.y) as _ {
+    pub fn new(first: A, second: B) -> Option<Self> {
+        if first.surface_size() == second.surface_size() {
             Some(Self {
-                slice,
-                size,
+                first,
+                second,
                 ghost: PhantomData,
             })
         } else {
@@ -173,164 +2859,299 @@ where
         }
     }
 
-    /// Constructs a new surface.
+    /// Size of both surfaces.
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.first.surface_size()
+    }
+
+    /// Get the values of both surfaces at (pt.x, pt.y).
     ///
-    /// Infers the height from slice length and width.
+    /// Returns `None` if either of them doesn't have a value there.
+    #[inline]
+    pub fn get(&self, pt: Point) -> Option<(&ItemA, &ItemB)> {
+        Some((self.first.surface_get(pt)?, self.second.surface_get(pt)?))
+    }
+
+    /// Returns an iterator over the positions and values of both surfaces in row-major order.
+    #[inline]
+    pub fn pixels(&self) -> impl Iterator<Item = (Point, (&ItemA, &ItemB))> {
+        let size = self.size();
+
+        (0..size.y)
+            .flat_map(move |y| (0..size.x).map(move |x| point(x, y)))
+            .filter_map(move |pt| Some((pt, self.get(pt)?)))
+    }
+
+    /// Returns references to the underlying surfaces.
+    #[inline]
+    pub fn inner(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Returns references to the underlying surfaces.
+    #[inline]
+    pub fn inner_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
+    }
+
+    /// Returns the underlying surfaces.
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+/// A surface that pads another surface with a border value on each side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PadSurface<S, Item> {
+    surface: S,
+    start: Size,
+    end: Size,
+    value: Item,
+}
+
+impl<S, Item> PadSurface<S, Item> {
+    /// Create a new `PadSurface`.
+    ///
+    /// `start` is the thickness of the left (x) and top (y) borders,
+    /// `end` is the thickness of the right (x) and bottom (y) borders.
+    /// The padded size is clamped to `u32::MAX`, cutting off whatever is past it.
     #[inline]
-    pub fn new_infer(slice: Slice, width: u32) -> Self {
+    pub fn new(surface: S, start: Size, end: Size, value: Item) -> Self {
         Self {
-            size: size(width, slice.as_ref().len() as u32 / width),
-            slice,
-            ghost: PhantomData,
+            surface,
+            start,
+            end,
+            value,
         }
     }
 
-    /// Returns the slice used to create the surface.
+    /// Create a new `PadSurface` with a border of the same thickness on each side.
     #[inline]
-    pub fn into_inner(self) -> Slice {
-        self.slice
+    pub fn uniform(surface: S, thickness: u32, value: Item) -> Self {
+        Self::new(
+            surface,
+            size(thickness, thickness),
+            size(thickness, thickness),
+            value,
+        )
     }
-}
 
-impl<Slice, Item> Deref for GenericSurface<Slice, Item>
-where
-    Slice: AsRef<[Item]>,
-{
-    type Target = [Item];
+    /// Thickness of the left (x) and top (y) borders.
+    #[inline]
+    pub fn start(&self) -> Size {
+        self.start
+    }
 
+    /// Thickness of the right (x) and bottom (y) borders.
     #[inline]
-    fn deref(&self) -> &Self::Target {
-        self.slice.as_ref()
+    pub fn end(&self) -> Size {
+        self.end
     }
-}
 
-impl<Slice, Item> DerefMut for GenericSurface<Slice, Item>
-where
-    Slice: AsRef<[Item]> + AsMut<[Item]>,
-{
+    /// Returns a reference to the border value.
     #[inline]
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.slice.as_mut()
+    pub fn value(&self) -> &Item {
+        &self.value
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns a reference to the underlying surface.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.surface
+    }
+
+    /// Returns the underlying surface.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
     }
 }
 
-impl<Slice, Item> Surface<Item> for GenericSurface<Slice, Item>
+impl<S, Item> Surface<Item> for PadSurface<S, Item>
 where
-    Slice: AsRef<[Item]>,
+    S: Surface<Item>,
 {
     #[inline]
     fn surface_size(&self) -> Size {
-        self.size
+        let inner_size = self.surface.surface_size();
+
+        size(
+            self.start
+                .x
+                .saturating_add(inner_size.x)
+                .saturating_add(self.end.x),
+            self.start
+                .y
+                .saturating_add(inner_size.y)
+                .saturating_add(self.end.y),
+        )
     }
 
     #[inline]
     fn surface_get(&self, pt: Point) -> Option<&Item> {
-        if pt.x < self.size.x && pt.y < self.size.y {
-            Some(
-                self.slice
-                    .as_ref()
-                    .index((pt.y * self.size.x + pt.x) as usize),
-            )
+        let outer_size = self.surface_size();
+
+        if size_contains(outer_size, pt) {
+            pt.x.checked_sub(self.start.x)
+                .zip(pt.y.checked_sub(self.start.y))
+                .and_then(|(x, y)| self.surface.surface_get(point(x, y)))
+                .or(Some(&self.value))
         } else {
             None
         }
     }
 }
 
-impl<Slice, Item> SurfaceMut<Item> for GenericSurface<Slice, Item>
-where
-    Slice: AsRef<[Item]> + AsMut<[Item]>,
-{
-    #[inline]
-    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
-        if pt.x < self.size.x && pt.y < self.size.y {
-            Some(
-                self.slice
-                    .as_mut()
-                    .index_mut((pt.y * self.size.x + pt.x) as usize),
-            )
-        } else {
-            None
+/// Grow `dirty` to also cover `rect`.
+#[inline]
+fn expand_dirty(dirty: &mut Option<Rect>, rect: Rect) {
+    *dirty = Some(match *dirty {
+        Some(dirty) => {
+            let start = point(
+                dirty.origin.x.min(rect.origin.x),
+                dirty.origin.y.min(rect.origin.y),
+            );
+            let end = point(
+                (dirty.origin.x.saturating_add(dirty.size.x))
+                    .max(rect.origin.x.saturating_add(rect.size.x)),
+                (dirty.origin.y.saturating_add(dirty.size.y))
+                    .max(rect.origin.y.saturating_add(rect.size.y)),
+            );
+
+            Rect::new(start, size(end.x - start.x, end.y - start.y))
         }
-    }
+        None => rect,
+    });
 }
 
-/// A 'surface' that holds a single value, like a plain-colored rectangle.
+/// A surface that records the bounding rectangle of all the values of another surface that were accessed mutably
+/// (the dirty rectangle), so only that part has to be presented.
+///
+/// The values are marked dirty on mutable access, not on actual change,
+/// so e.g. blitting identical values still marks them (see [`ChangeTrackedSurface`] for that).
+/// Rows aren't provided for mutation (so [`blit`](crate::blit) copies value by value),
+/// as a probed row would have to be marked dirty as a whole.
+///
+/// With the `serde` feature, only the wrapped surface is serialized: a deserialized surface has nothing marked dirty.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct SingleValueSurface<T> {
-    /// Surface size.
-    pub size: Size,
-    /// Stored value, likely a color.
-    pub value: T,
+pub struct TrackedSurface<S, Item> {
+    surface: S,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dirty: Option<Rect>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ghost: PhantomData<Item>,
 }
 
-impl<T> SingleValueSurface<T> {
-    /// Construct a new surface.
+impl<S, Item> TrackedSurface<S, Item> {
+    /// Create a new `TrackedSurface` with nothing marked dirty.
     #[inline]
-    pub const fn new(value: T, size: Size) -> Self {
-        Self { size, value }
+    pub fn new(surface: S) -> Self {
+        Self {
+            surface,
+            dirty: None,
+            ghost: PhantomData,
+        }
     }
 
-    /// Returns a reference to the underlying value.
+    /// Returns the dirty rectangle, `None` if nothing was accessed mutably.
     #[inline]
-    pub fn value(&self) -> &T {
-        &self.value
+    pub fn dirty(&self) -> Option<Rect> {
+        self.dirty
     }
 
-    /// Returns a reference to the underlying value.
+    /// Returns the dirty rectangle and resets it.
     #[inline]
-    pub fn value_mut(&mut self) -> &mut T {
-        &mut self.value
+    pub fn take_dirty(&mut self) -> Option<Rect> {
+        self.dirty.take()
     }
 
-    /// Returns the underlying value.
+    /// Returns a reference to the underlying surface.
     #[inline]
-    pub fn into_inner(self) -> T {
-        self.value
+    pub fn inner(&self) -> &S {
+        &self.surface
+    }
+
+    /// Returns the underlying surface.
+    ///
+    /// There's no `inner_mut`, as changes through it couldn't be tracked.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.surface
     }
 }
 
-impl<T> Surface<T> for SingleValueSurface<T> {
+impl<S, Item> Surface<Item> for TrackedSurface<S, Item>
+where
+    S: Surface<Item>,
+{
     #[inline]
     fn surface_size(&self) -> Size {
-        self.size
+        self.surface.surface_size()
     }
 
     #[inline]
-    fn surface_get(&self, _pt: Point) -> Option<&T> {
-        Some(&self.value)
+    fn surface_get(&self, pt: Point) -> Option<&Item> {
+        self.surface.surface_get(pt)
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[Item]> {
+        self.surface.surface_row(y)
     }
 }
 
-impl<T> SurfaceMut<T> for SingleValueSurface<T> {
+impl<S, Item> SurfaceMut<Item> for TrackedSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+{
     #[inline]
-    fn surface_get_mut(&mut self, _pt: Point) -> Option<&mut T> {
-        Some(&mut self.value)
+    fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
+        let value = self.surface.surface_get_mut(pt)?;
+        expand_dirty(&mut self.dirty, Rect::new(pt, size(1, 1)));
+
+        Some(value)
     }
 }
 
-/// A surface that only uses a rectangular part of another surface.
+/// A surface that records the bounding rectangle of the values of another surface that actually changed
+/// (the dirty rectangle), so overwriting values with identical ones doesn't cause repaints.
+///
+/// [`SurfaceMut::surface_get_mut`] returns a reference, so the new value isn't known when it's called.
+/// Instead, the old value is cloned on every mutable access and compared to the current one
+/// on the next access (or when the dirty rectangle is requested).
+/// [`ChangeTrackedSurface::set`] compares right away and only writes if the value differs.
+/// Use [`TrackedSurface`] if cloning the values is too expensive.
+///
+/// With the `serde` feature, only the wrapped surface is serialized: a deserialized surface has nothing marked dirty.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct SubSurface<S, Item> {
+pub struct ChangeTrackedSurface<S, Item> {
     surface: S,
-    offset: Point,
-    size: Size,
-    ghost: PhantomData<Item>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dirty: Option<Rect>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pending: Option<(Point, Item)>,
 }
 
-impl<S, Item> SubSurface<S, Item> {
-    /// Position of the rectangular part on the original surface.
-    #[inline]
-    pub fn offset(&self) -> Point {
-        self.offset
-    }
-
-    /// Size of the rectangular part (size of the `SubSurface` itself).
+impl<S, Item> ChangeTrackedSurface<S, Item> {
+    /// Create a new `ChangeTrackedSurface` with nothing marked dirty.
     #[inline]
-    pub fn size(&self) -> Size {
-        self.size
+    pub fn new(surface: S) -> Self {
+        Self {
+            surface,
+            dirty: None,
+            pending: None,
+        }
     }
 
     /// Returns a reference to the underlying surface.
@@ -339,66 +3160,107 @@ impl<S, Item> SubSurface<S, Item> {
         &self.surface
     }
 
-    /// Returns a reference to the underlying surface.
-    #[inline]
-    pub fn inner_mut(&mut self) -> &mut S {
-        &mut self.surface
-    }
-
     /// Returns the underlying surface.
+    ///
+    /// There's no `inner_mut`, as changes through it couldn't be tracked.
     #[inline]
     pub fn into_inner(self) -> S {
         self.surface
     }
 }
 
-impl<S, Item> SubSurface<S, Item>
+impl<S, Item> ChangeTrackedSurface<S, Item>
 where
     S: Surface<Item>,
+    Item: PartialEq,
 {
-    /// Create a new `SubSurface`.
+    /// The position of the last mutable access, if the value there has changed.
     #[inline]
-    pub fn new(surface: S, offset: Point, size: Size) -> Self {
-        Self {
-            surface,
-            offset,
-            size,
-            ghost: PhantomData,
+    fn pending_change(&self) -> Option<Point> {
+        let (pt, old) = self.pending.as_ref()?;
+
+        (self.surface.surface_get(*pt) != Some(old)).then_some(*pt)
+    }
+
+    /// Returns the dirty rectangle, `None` if nothing has changed.
+    #[inline]
+    pub fn dirty(&self) -> Option<Rect> {
+        let mut dirty = self.dirty;
+
+        if let Some(pt) = self.pending_change() {
+            expand_dirty(&mut dirty, Rect::new(pt, size(1, 1)));
+        }
+
+        dirty
+    }
+
+    /// Returns the dirty rectangle and resets it.
+    #[inline]
+    pub fn take_dirty(&mut self) -> Option<Rect> {
+        let dirty = self.dirty();
+
+        self.dirty = None;
+        self.pending = None;
+
+        dirty
+    }
+}
+
+impl<S, Item> ChangeTrackedSurface<S, Item>
+where
+    S: SurfaceMut<Item>,
+    Item: PartialEq,
+{
+    /// Write `value` at `pt` if it differs from the current value, marking it dirty.
+    ///
+    /// Returns `true` if the value was written.
+    #[inline]
+    pub fn set(&mut self, pt: Point, value: Item) -> bool {
+        self.dirty = self.dirty();
+        self.pending = None;
+
+        match self.surface.surface_get_mut(pt) {
+            Some(current) if *current != value => {
+                *current = value;
+                expand_dirty(&mut self.dirty, Rect::new(pt, size(1, 1)));
+                true
+            }
+            _ => false,
         }
     }
 }
 
-impl<S, Item> Surface<Item> for SubSurface<S, Item>
+impl<S, Item> Surface<Item> for ChangeTrackedSurface<S, Item>
 where
     S: Surface<Item>,
 {
     #[inline]
     fn surface_size(&self) -> Size {
-        self.size
+        self.surface.surface_size()
     }
 
     #[inline]
     fn surface_get(&self, pt: Point) -> Option<&Item> {
-        if pt.x < self.size.x && pt.y < self.size.y {
-            self.surface
-                .surface_get(point(pt.x + self.offset.x, pt.y + self.offset.y))
-        } else {
-            None
-        }
+        self.surface.surface_get(pt)
+    }
+
+    #[inline]
+    fn surface_row(&self, y: u32) -> Option<&[Item]> {
+        self.surface.surface_row(y)
     }
 }
 
-impl<S, Item> SurfaceMut<Item> for SubSurface<S, Item>
+impl<S, Item> SurfaceMut<Item> for ChangeTrackedSurface<S, Item>
 where
     S: SurfaceMut<Item>,
+    Item: Clone + PartialEq,
 {
     #[inline]
     fn surface_get_mut(&mut self, pt: Point) -> Option<&mut Item> {
-        if pt.x < self.size.x && pt.y < self.size.y {
-            self.surface
-                .surface_get_mut(point(pt.x + self.offset.x, pt.y + self.offset.y))
-        } else {
-            None
-        }
+        // settle the previous access before starting a new one
+        self.dirty = self.dirty();
+        self.pending = self.surface.surface_get(pt).map(|old| (pt, old.clone()));
+
+        self.surface.surface_get_mut(pt)
     }
 }